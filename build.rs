@@ -23,6 +23,8 @@ struct Category {
     display_name: String,
     file_prefix: String,
     cli_description: String,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
     #[serde(default)]
     frequencies: Vec<Frequency>,
 }
@@ -32,6 +34,22 @@ struct Frequency {
     hz: f64,
     name: String,
     description: String,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+}
+
+/// Entries without an `enabled` key are included
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// Drop entries, and whole categories, marked `enabled = false`
+fn enabled_only(mut db: FrequencyDb) -> FrequencyDb {
+    db.categories.retain(|cat| cat.enabled);
+    for cat in &mut db.categories {
+        cat.frequencies.retain(|f| f.enabled);
+    }
+    db
 }
 
 fn to_pascal_case(s: &str) -> String {
@@ -59,6 +77,7 @@ fn main() {
 
     let toml_content = fs::read_to_string(&toml_path).expect("Failed to read frequencies.toml");
     let db: FrequencyDb = toml::from_str(&toml_content).expect("Failed to parse frequencies.toml");
+    let db = enabled_only(db);
 
     generate_frequency_module(&db, &out_dir);
     generate_cli_commands(&db, &out_dir);
@@ -273,3 +292,65 @@ fn generate_cli_commands(db: &FrequencyDb, out_dir: &str) {
 
     fs::write(Path::new(out_dir).join("cli_commands.rs"), &output).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_entries_and_categories_are_dropped() {
+        let db: FrequencyDb = toml::from_str(
+            r#"
+            [[categories]]
+            id = "kept"
+            command = "Kept"
+            dir_name = "kept"
+            display_name = "Kept"
+            file_prefix = "kept"
+            cli_description = "Kept category"
+
+            [[categories.frequencies]]
+            hz = 100.0
+            name = "default"
+            description = "No enabled key"
+
+            [[categories.frequencies]]
+            hz = 200.0
+            name = "off"
+            description = "Disabled entry"
+            enabled = false
+
+            [[categories.frequencies]]
+            hz = 300.0
+            name = "on"
+            description = "Explicitly enabled"
+            enabled = true
+
+            [[categories]]
+            id = "dropped"
+            command = "Dropped"
+            dir_name = "dropped"
+            display_name = "Dropped"
+            file_prefix = "dropped"
+            cli_description = "Disabled category"
+            enabled = false
+
+            [[categories.frequencies]]
+            hz = 400.0
+            name = "inside"
+            description = "Entry of a disabled category"
+            "#,
+        )
+        .unwrap();
+
+        let db = enabled_only(db);
+        let ids: Vec<&str> = db.categories.iter().map(|cat| cat.id.as_str()).collect();
+        assert_eq!(ids, ["kept"]);
+        let names: Vec<&str> = db.categories[0]
+            .frequencies
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, ["default", "on"]);
+    }
+}
@@ -0,0 +1,264 @@
+//! Signal analysis helpers.
+//!
+//! Lightweight spectrum tools used to verify and report on generated audio
+//! without pulling in an external FFT dependency.
+
+use std::f64::consts::PI;
+
+use crate::effects::Biquad;
+
+/// Largest analysis window, in samples (must be a power of two)
+const MAX_WINDOW: usize = 1 << 16;
+
+/// Compute the magnitude spectrum of the samples using a radix-2 FFT
+///
+/// The input is truncated to the largest power of two that fits (capped at
+/// `MAX_WINDOW`) and Hann-windowed. Returns magnitudes for bins `0..=n/2`,
+/// where bin `k` corresponds to `k * sample_rate / n` Hz.
+pub fn magnitude_spectrum(samples: &[f64]) -> Vec<f64> {
+    let n = window_len(samples.len());
+    if n < 2 {
+        return Vec::new();
+    }
+
+    let mut re: Vec<f64> = samples[..n]
+        .iter()
+        .enumerate()
+        .map(|(i, &s)| s * 0.5 * (1.0 - (2.0 * PI * i as f64 / n as f64).cos()))
+        .collect();
+    let mut im = vec![0.0; n];
+    fft_in_place(&mut re, &mut im);
+
+    (0..=n / 2).map(|k| re[k].hypot(im[k])).collect()
+}
+
+/// Sum spectral energy into `bands` log-spaced bands between `min_hz` and Nyquist
+///
+/// Long inputs are analyzed in half-overlapping `MAX_WINDOW` blocks so the whole
+/// signal contributes. Returns `(low_hz, high_hz, energy)` for each band.
+pub fn band_energies(
+    samples: &[f64],
+    sample_rate: u32,
+    min_hz: f64,
+    bands: usize,
+) -> Vec<(f64, f64, f64)> {
+    let nyquist = sample_rate as f64 / 2.0;
+    let ratio = (nyquist / min_hz).powf(1.0 / bands.max(1) as f64);
+    let mut result: Vec<(f64, f64, f64)> = (0..bands)
+        .map(|band| {
+            let lo = min_hz * ratio.powi(band as i32);
+            (lo, lo * ratio, 0.0)
+        })
+        .collect();
+
+    let blocks: Vec<&[f64]> = if samples.len() > MAX_WINDOW {
+        (0..=samples.len() - MAX_WINDOW)
+            .step_by(MAX_WINDOW / 2)
+            .map(|start| &samples[start..start + MAX_WINDOW])
+            .collect()
+    } else {
+        vec![samples]
+    };
+
+    for block in blocks {
+        let spectrum = magnitude_spectrum(block);
+        if spectrum.len() < 2 {
+            continue;
+        }
+        let bin_hz = sample_rate as f64 / ((spectrum.len() - 1) * 2) as f64;
+        for (k, magnitude) in spectrum.iter().enumerate() {
+            let hz = k as f64 * bin_hz;
+            if let Some(band) = result.iter_mut().find(|(lo, hi, _)| hz >= *lo && hz < *hi) {
+                band.2 += magnitude * magnitude;
+            }
+        }
+    }
+
+    result
+}
+
+/// Find the strongest frequency between `min_hz` and `max_hz`
+///
+/// Uses parabolic interpolation around the peak bin for sub-bin accuracy.
+pub fn dominant_frequency(samples: &[f64], sample_rate: u32, min_hz: f64, max_hz: f64) -> f64 {
+    let spectrum = magnitude_spectrum(samples);
+    if spectrum.len() < 3 {
+        return 0.0;
+    }
+
+    let bin_hz = sample_rate as f64 / ((spectrum.len() - 1) * 2) as f64;
+    let lo = ((min_hz / bin_hz).floor() as usize).max(1);
+    let hi = ((max_hz / bin_hz).ceil() as usize).min(spectrum.len() - 2);
+    if lo > hi {
+        return 0.0;
+    }
+
+    let peak = (lo..=hi)
+        .max_by(|&a, &b| spectrum[a].total_cmp(&spectrum[b]))
+        .unwrap_or(lo);
+
+    let (a, b, c) = (spectrum[peak - 1], spectrum[peak], spectrum[peak + 1]);
+    let denom = a - 2.0 * b + c;
+    let offset = if denom.abs() > f64::EPSILON {
+        0.5 * (a - c) / denom
+    } else {
+        0.0
+    };
+
+    (peak as f64 + offset) * bin_hz
+}
+
+/// Find the strongest amplitude-modulation rate between `min_hz` and `max_hz`
+///
+/// Squaring the signal demodulates its envelope, so pulses and beats show up
+/// as spectral peaks at their modulation rate.
+pub fn modulation_frequency(samples: &[f64], sample_rate: u32, min_hz: f64, max_hz: f64) -> f64 {
+    let power: Vec<f64> = samples.iter().map(|s| s * s).collect();
+    dominant_frequency(&power, sample_rate, min_hz, max_hz)
+}
+
+/// Root-mean-square level of the samples
+pub fn rms(samples: &[f64]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f64>() / samples.len() as f64).sqrt()
+}
+
+/// Peak-to-RMS ratio in dB (3.01 dB for a sine, 0 dB for a square wave)
+pub fn crest_factor_db(samples: &[f64]) -> f64 {
+    let peak = samples.iter().fold(0.0f64, |acc, s| acc.max(s.abs()));
+    let rms = rms(samples);
+    if rms > 0.0 {
+        20.0 * (peak / rms).log10()
+    } else {
+        0.0
+    }
+}
+
+/// Integrated loudness in LUFS (simplified ITU-R BS.1770)
+///
+/// Each channel is K-weighted (high shelf + high-pass), mean square power is
+/// measured over 400 ms blocks with 75% overlap, and blocks are gated at
+/// -70 LUFS absolute and -10 LU relative before averaging.
+pub fn integrated_loudness(channels: &[Vec<f64>], sample_rate: u32) -> f64 {
+    let weighted: Vec<Vec<f64>> = channels
+        .iter()
+        .map(|channel| {
+            let mut samples = channel.clone();
+            Biquad::high_shelf(sample_rate, 1500.0, 4.0, std::f64::consts::FRAC_1_SQRT_2)
+                .process(&mut samples);
+            Biquad::highpass_q(sample_rate, 38.0, 0.5).process(&mut samples);
+            samples
+        })
+        .collect();
+
+    let len = weighted.iter().map(Vec::len).min().unwrap_or(0);
+    let block = ((0.4 * sample_rate as f64) as usize).clamp(1, len.max(1));
+    let step = (block / 4).max(1);
+
+    let block_power = |start: usize| -> f64 {
+        weighted
+            .iter()
+            .map(|channel| {
+                channel[start..start + block]
+                    .iter()
+                    .map(|s| s * s)
+                    .sum::<f64>()
+            })
+            .sum::<f64>()
+            / block as f64
+    };
+    let to_lufs = |power: f64| -0.691 + 10.0 * power.log10();
+
+    let powers: Vec<f64> = (0..=len.saturating_sub(block))
+        .step_by(step)
+        .filter(|&start| start + block <= len)
+        .map(block_power)
+        .collect();
+
+    let gated_mean = |threshold: f64| -> f64 {
+        let kept: Vec<f64> = powers
+            .iter()
+            .copied()
+            .filter(|&p| to_lufs(p) > threshold)
+            .collect();
+        if kept.is_empty() {
+            0.0
+        } else {
+            kept.iter().sum::<f64>() / kept.len() as f64
+        }
+    };
+
+    let relative_gate = to_lufs(gated_mean(-70.0)) - 10.0;
+    to_lufs(gated_mean(relative_gate.max(-70.0)))
+}
+
+/// Largest power of two not exceeding `len`, capped at `MAX_WINDOW`
+fn window_len(len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    let pow = 1usize << (usize::BITS - 1 - len.leading_zeros());
+    pow.min(MAX_WINDOW)
+}
+
+/// Iterative radix-2 Cooley-Tukey FFT (length must be a power of two)
+fn fft_in_place(re: &mut [f64], im: &mut [f64]) {
+    let n = re.len();
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_im, w_re) = (angle * k as f64).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_tone_dominates_one_band() {
+        let samples: Vec<f64> = (0..44100)
+            .map(|i| (2.0 * std::f64::consts::PI * 1000.0 * i as f64 / 44100.0).sin())
+            .collect();
+        let bands = band_energies(&samples, 44100, 20.0, 12);
+        let total: f64 = bands.iter().map(|&(_, _, energy)| energy).sum();
+
+        let loud: Vec<&(f64, f64, f64)> = bands
+            .iter()
+            .filter(|&&(_, _, energy)| energy > 0.1 * total)
+            .collect();
+        assert_eq!(loud.len(), 1);
+        let (lo, hi, energy) = *loud[0];
+        assert!(lo <= 1000.0 && 1000.0 < hi);
+        assert!(energy > 0.9 * total);
+    }
+}
@@ -0,0 +1,83 @@
+//! ZIP archives of generated output.
+
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Write `files` into a ZIP archive at `archive`, returning the entry count
+///
+/// Entries are named relative to `dir`, so category subdirectories are kept
+/// (e.g. `solfeggio/solfeggio_528_528.00hz.wav`); a file outside `dir` is
+/// stored under its file name alone.
+pub fn write_archive(archive: &Path, dir: &Path, files: &[PathBuf]) -> io::Result<usize> {
+    if let Some(parent) = archive.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut zip = ZipWriter::new(File::create(archive)?);
+    for file in files {
+        let name = entry_name(file, dir);
+        let size = fs::metadata(file)?.len();
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(size >= u32::MAX as u64);
+
+        zip.start_file(name, options)?;
+        io::copy(&mut File::open(file)?, &mut zip)?;
+    }
+    zip.finish()?;
+    Ok(files.len())
+}
+
+/// Archive entry name for `file`, with `/` separators on every platform
+fn entry_name(file: &Path, dir: &Path) -> String {
+    let relative = match file.strip_prefix(dir) {
+        Ok(relative) => relative,
+        Err(_) => Path::new(file.file_name().unwrap_or(file.as_os_str())),
+    };
+    relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frequency::Category;
+    use crate::test_support;
+    use zip::ZipArchive;
+
+    #[test]
+    fn archive_holds_each_category_file_under_its_subdirectory() {
+        let gen = test_support::generator("archive", 0.05);
+        gen.generate_category(Category::Chakras).unwrap();
+        let files = gen.written_files();
+        let dir_name = Category::Chakras.dir_name();
+
+        let path = gen.output_dir.join("out").join("chakras.zip");
+        let count = write_archive(&path, &gen.output_dir, &files).unwrap();
+        assert_eq!(count, files.len());
+
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        let mut expected: Vec<String> = files
+            .iter()
+            .map(|file| {
+                let name = file.file_name().unwrap().to_string_lossy();
+                format!("{}/{}", dir_name, name)
+            })
+            .collect();
+        expected.sort();
+        assert_eq!(names, expected);
+
+        let first = archive.by_name(&expected[0]).unwrap();
+        let on_disk = fs::metadata(gen.output_dir.join(&expected[0])).unwrap();
+        assert_eq!(first.size(), on_disk.len());
+    }
+}
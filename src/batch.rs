@@ -0,0 +1,197 @@
+//! Batch rendering from a JSON manifest.
+//!
+//! A manifest is a JSON array of jobs, each naming a render type, its
+//! parameters, an output filename, and an optional duration override:
+//!
+//! ```json
+//! [
+//!   { "type": "sine", "frequency": 528, "output": "love.wav", "duration": 30 },
+//!   { "type": "binaural", "frequency": 7.83, "base": 200, "output": "schumann.wav" },
+//!   { "type": "noise", "color": "pink", "output": "bed.wav" }
+//! ]
+//! ```
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::SpiritError;
+use crate::generator::{AudioGenerator, NoiseColor};
+
+/// Default carrier for binaural and isochronic jobs
+fn default_carrier() -> f64 {
+    200.0
+}
+
+/// Render type and its parameters
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum JobKind {
+    Sine {
+        frequency: f64,
+    },
+    Binaural {
+        frequency: f64,
+        #[serde(default = "default_carrier")]
+        base: f64,
+    },
+    Isochronic {
+        frequency: f64,
+        #[serde(default = "default_carrier")]
+        carrier: f64,
+    },
+    Layer {
+        frequencies: Vec<f64>,
+    },
+    Drone {
+        frequencies: Vec<f64>,
+    },
+    Bowl {
+        frequency: f64,
+        decay: Option<f64>,
+    },
+    Sweep {
+        start: f64,
+        end: f64,
+    },
+    Noise {
+        color: NoiseColor,
+    },
+}
+
+/// A single render job from the manifest
+#[derive(Debug, Deserialize)]
+struct BatchJob {
+    #[serde(flatten)]
+    kind: JobKind,
+    /// Output filename, relative to the output directory
+    output: String,
+    /// Duration override in seconds
+    duration: Option<f64>,
+}
+
+/// Run every job in the manifest, printing a per-job summary
+///
+/// Jobs are independent: a failing job is reported and the rest still run.
+pub fn run_manifest(
+    gen: &AudioGenerator,
+    manifest: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let jobs: Vec<BatchJob> = serde_json::from_str(&fs::read_to_string(manifest)?)?;
+
+    println!(
+        "\n=== Running Batch: {} job(s) from {} ===",
+        jobs.len(),
+        manifest.display()
+    );
+
+    let results: Vec<(&str, Result<(), SpiritError>)> = jobs
+        .iter()
+        .take_while(|_| !gen.is_cancelled())
+        .map(|job| (job.output.as_str(), render_job(gen, job)))
+        .collect();
+
+    println!("\n=== Batch Summary ===");
+    let mut failed = 0;
+    for (output, result) in &results {
+        match result {
+            Ok(()) => println!("  ok      {}", output),
+            Err(err) => {
+                failed += 1;
+                println!("  FAILED  {}: {}", output, err);
+            }
+        }
+    }
+    println!("  {} succeeded, {} failed", results.len() - failed, failed);
+
+    if failed > 0 {
+        return Err(format!("{} batch job(s) failed", failed).into());
+    }
+    Ok(())
+}
+
+/// Render a single job to its output file
+fn render_job(gen: &AudioGenerator, job: &BatchJob) -> Result<(), SpiritError> {
+    let duration = job.duration.unwrap_or(gen.duration);
+    let path = gen.output_dir.join(&job.output);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    match &job.kind {
+        JobKind::Sine { frequency } => {
+            gen.save_mono_wav(&path, &gen.generate_sine_wave(*frequency, duration))
+        }
+        JobKind::Binaural { frequency, base } => gen.save_stereo_wav(
+            &path,
+            &gen.generate_binaural_beat(*base, *frequency, duration),
+        ),
+        JobKind::Isochronic { frequency, carrier } => gen.save_mono_wav(
+            &path,
+            &gen.generate_isochronic_tone(*carrier, *frequency, duration),
+        ),
+        JobKind::Layer { frequencies } => gen.save_mono_wav(
+            &path,
+            &gen.generate_layered_frequencies(frequencies, duration),
+        ),
+        JobKind::Drone { frequencies } => {
+            gen.save_mono_wav(&path, &gen.generate_drone(frequencies, duration))
+        }
+        JobKind::Bowl { frequency, decay } => gen.save_mono_wav(
+            &path,
+            &gen.generate_singing_bowl(*frequency, duration, *decay),
+        ),
+        JobKind::Sweep { start, end } => {
+            let (start, end) = gen.validate_sweep_endpoints(*start, *end)?;
+            gen.save_mono_wav(&path, &gen.generate_frequency_sweep(start, end, duration))
+        }
+        JobKind::Noise { color } => gen.save_mono_wav(&path, &gen.generate_noise(*color, duration)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn two_job_manifest_writes_both_files() {
+        let gen = test_support::generator("batch", 0.5);
+        let manifest = gen.output_dir.join("jobs.json");
+        fs::write(
+            &manifest,
+            r#"[
+                { "type": "sine", "frequency": 528, "output": "love.wav" },
+                { "type": "binaural", "frequency": 7.83, "output": "nested/schumann.wav", "duration": 1 }
+            ]"#,
+        )
+        .unwrap();
+
+        run_manifest(&gen, &manifest).unwrap();
+
+        let (channels, rate, samples) = test_support::read(&gen.output_dir.join("love.wav"));
+        assert_eq!((channels, samples.len()), (1, rate as usize / 2));
+        let (channels, rate, samples) =
+            test_support::read(&gen.output_dir.join("nested/schumann.wav"));
+        assert_eq!((channels, samples.len()), (2, 2 * rate as usize));
+    }
+
+    #[test]
+    fn failing_job_does_not_stop_the_batch() {
+        let gen = test_support::generator("batch-failure", 0.5);
+        let manifest = gen.output_dir.join("jobs.json");
+        fs::write(
+            &manifest,
+            r#"[
+                { "type": "sweep", "start": 0, "end": 1000, "output": "bad.wav" },
+                { "type": "noise", "color": "pink", "output": "bed.wav" }
+            ]"#,
+        )
+        .unwrap();
+
+        assert!(run_manifest(&gen, &manifest).is_err());
+        assert!(!gen.output_dir.join("bad.wav").exists());
+        assert!(gen.output_dir.join("bed.wav").exists());
+    }
+}
@@ -0,0 +1,81 @@
+//! SHA-256 manifests for verifying generated files.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+/// Manifest filename written into the output directory
+pub const MANIFEST_NAME: &str = "manifest.sha256";
+
+/// Write a `sha256sum`-compatible manifest of `files` into `dir`
+///
+/// Each entry is preceded by a `#` comment with the file size; paths are
+/// relative to `dir` where possible so the manifest can be checked in place
+/// with `sha256sum -c`.
+pub fn write_manifest(dir: &Path, files: &[PathBuf]) -> io::Result<PathBuf> {
+    let mut manifest = String::new();
+    for file in files {
+        let bytes = fs::read(file)?;
+        let hash: String = Sha256::digest(&bytes)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let name = file.strip_prefix(dir).unwrap_or(file);
+
+        manifest.push_str(&format!("# {} bytes\n", bytes.len()));
+        manifest.push_str(&format!("{}  {}\n", hash, name.display()));
+    }
+
+    let path = dir.join(MANIFEST_NAME);
+    fs::write(&path, manifest)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{GenerationMode, DEFAULT_CARRIER_HZ};
+    use crate::test_support;
+
+    #[test]
+    fn manifest_lists_each_written_file() {
+        let gen = test_support::generator("manifest", 0.25);
+        for hz in [432.0, 528.0] {
+            gen.generate_custom(hz, &GenerationMode::Sine, DEFAULT_CARRIER_HZ)
+                .unwrap();
+        }
+        let files = gen.written_files();
+        assert_eq!(files.len(), 2);
+
+        let manifest =
+            fs::read_to_string(write_manifest(&gen.output_dir, &files).unwrap()).unwrap();
+        let entries: Vec<&str> = manifest.lines().filter(|l| !l.starts_with('#')).collect();
+        assert_eq!(entries.len(), files.len());
+        for file in &files {
+            let bytes = fs::read(file).unwrap();
+            let name = file.file_name().unwrap().to_string_lossy();
+            let hash: String = Sha256::digest(&bytes)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            assert!(entries.contains(&format!("{}  {}", hash, name).as_str()));
+            assert!(manifest.contains(&format!("# {} bytes", bytes.len())));
+        }
+    }
+
+    #[test]
+    fn manifest_hash_matches_a_known_digest() {
+        let dir = test_support::temp_dir("manifest-known");
+        let file = dir.join("abc.txt");
+        fs::write(&file, "abc").unwrap();
+
+        let manifest = fs::read_to_string(write_manifest(&dir, &[file]).unwrap()).unwrap();
+        assert_eq!(
+            manifest,
+            "# 3 bytes\n\
+             ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  abc.txt\n"
+        );
+    }
+}
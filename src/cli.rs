@@ -1,11 +1,23 @@
 //! Command-line interface definitions.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
-use crate::frequency::{Category, BRAINWAVE_STATES};
-use crate::generator::GenerationMode;
+use crate::effects::EnvelopePreset;
+use crate::encode::OutputFormat;
+use crate::frequency::{
+    frequency_issues, hz_to_note, parse_frequency_filename, Category, FrequencyInfo, TuningSystem,
+    BRAINWAVE_STATES, DEFAULT_A4_HZ, PYTHAGOREAN_COMMA,
+};
+use crate::generator::{
+    BandEdge, FadeWindow, GenerationMode, NoiseColor, SetOperation, TestSignal, DEFAULT_CARRIER_HZ,
+    OM_HZ,
+};
+use crate::oscillator::Waveform;
+use crate::resample::Resampler;
+use crate::rng::RngKind;
+use crate::wav::ChannelLayout;
 
 #[derive(Parser)]
 #[command(name = "spirit")]
@@ -15,7 +27,7 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
 
-    /// Output directory
+    /// Output directory; supports {date}, {time}, {category} and {run} placeholders
     #[arg(short, long, default_value = "./output")]
     pub output: PathBuf,
 
@@ -23,13 +35,247 @@ pub struct Cli {
     #[arg(short, long, default_value = "60")]
     pub duration: f64,
 
-    /// Sample rate in Hz (44100, 48000, 96000, 192000)
-    #[arg(short, long, default_value = "44100")]
-    pub sample_rate: u32,
+    /// Sample rate in Hz (44100, 48000, 96000, 192000), or "auto" to fit the content
+    #[arg(short, long, default_value = "44100", value_parser = parse_sample_rate)]
+    pub sample_rate: SampleRate,
 
-    /// Bit depth (16, 24, or 32)
+    /// Bit depth (16, 24, 32, or 64; 64 is always float)
     #[arg(short, long, default_value = "16")]
     pub bit_depth: u16,
+
+    /// Also encode every generated file to this format with ffmpeg (repeatable)
+    #[arg(long, value_delimiter = ',')]
+    pub also_format: Vec<OutputFormat>,
+
+    /// Add TPDF dither when writing 16-bit files
+    #[arg(long)]
+    pub dither: bool,
+
+    /// Noise-shape the 16-bit dither so its noise sits at high frequencies (implies --dither)
+    #[arg(long)]
+    pub dither_noise_shaping: bool,
+
+    /// Write IEEE float samples (32 or 64 bit) instead of integer PCM
+    #[arg(long)]
+    pub float: bool,
+
+    /// Append to existing output files instead of overwriting them
+    #[arg(long)]
+    pub append: bool,
+
+    /// Refuse to write any file estimated to exceed this many megabytes
+    #[arg(long, value_name = "MB")]
+    pub max_file_size: Option<f64>,
+
+    /// Write files even when they exceed --max-file-size
+    #[arg(long, requires = "max_file_size")]
+    pub force: bool,
+
+    /// Skip files that already exist as complete WAVs of the expected length
+    #[arg(long, conflicts_with = "append")]
+    pub resume: bool,
+
+    /// Fade curve shape
+    #[arg(long, default_value = "linear")]
+    pub fade_window: FadeWindow,
+
+    /// Flat center fraction for the Tukey fade window (0.0-1.0)
+    #[arg(long, default_value = "0.5")]
+    pub tukey_flat: f64,
+
+    /// Disable fade-in/out on all generators (useful for seamless loops)
+    #[arg(long)]
+    pub no_fade: bool,
+
+    /// Effects chain applied before writing, e.g. "lowpass:2000,reverb:0.3,normalize:0.95"
+    /// (lowpass, highpass, reverb, tremolo, normalize, limiter, bitcrush, saturate)
+    #[arg(long)]
+    pub fx: Option<String>,
+
+    /// Shape every output's amplitude with the envelope of this reference WAV
+    #[arg(long, value_name = "FILE")]
+    pub envelope_from: Option<PathBuf>,
+
+    /// Analog-style tanh saturation with this drive (around 1-5), level-matched to the input
+    #[arg(long, value_name = "DRIVE")]
+    pub warmth: Option<f64>,
+
+    /// Run the effects chain 2x oversampled for the Rife category, whose tones reach 10 kHz
+    #[arg(long)]
+    pub antialias: bool,
+
+    /// Lo-fi bit crusher: quantize to this many effective bits after the effects chain
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=24), value_name = "BITS")]
+    pub bitcrush: Option<u32>,
+
+    /// With --bitcrush, hold each sample this many times to mimic a lower sample rate
+    #[arg(long, default_value = "1", requires = "bitcrush")]
+    pub bitcrush_hold: usize,
+
+    /// Named ADSR envelope for custom and note output (pad, pluck, swell, gate)
+    #[arg(long)]
+    pub envelope_preset: Option<EnvelopePreset>,
+
+    /// Stereo balance from -1.0 (left only) to 1.0 (right only)
+    #[arg(long, default_value = "0", allow_negative_numbers = true)]
+    pub balance: f64,
+
+    /// Seed for noise and random phase generation
+    #[arg(long, default_value_t = crate::rng::DEFAULT_SEED)]
+    pub seed: u64,
+
+    /// PRNG algorithm behind noise and random phase
+    #[arg(long, default_value = "lcg")]
+    pub rng: RngKind,
+
+    /// Randomize the initial phase of layered frequencies to reduce peaks
+    #[arg(long)]
+    pub random_phase: bool,
+
+    /// Shape layer and comb files to this peak-to-RMS ratio in dB, choosing
+    /// phases and limiting peaks as needed (a sine is 3.01 dB)
+    #[arg(long, value_name = "DB")]
+    pub crest_factor: Option<f64>,
+
+    /// Normalize integrated loudness to this level in LUFS (e.g. -16)
+    #[arg(long, allow_negative_numbers = true)]
+    pub target_lufs: Option<f64>,
+
+    /// Mix noise of this color under the generated audio
+    #[arg(long)]
+    pub mix_noise: Option<NoiseColor>,
+
+    /// Level of the noise mixed in with --mix-noise
+    #[arg(long, default_value = "0.2")]
+    pub noise_level: f64,
+
+    /// Raise the tone's level wherever the mixed-in noise gets louder
+    #[arg(long, requires = "mix_noise")]
+    pub tone_auto_gain: bool,
+
+    /// Start each database tone with beeps counting its hundreds and tens digits
+    /// (528 Hz: 5 beeps, then 2; a zero digit is one long, lower beep)
+    #[arg(long)]
+    pub beep_prefix: bool,
+
+    /// Render category commands (and the sampler) as one sequential file
+    #[arg(long)]
+    pub concat: bool,
+
+    /// With --concat, also write each category tone as its own file alongside the master
+    #[arg(long, requires = "concat")]
+    pub both: bool,
+
+    /// With --concat, match every segment to the same RMS level
+    #[arg(long, requires = "concat")]
+    pub match_loudness: bool,
+
+    /// With --concat, render `all` as one file with this many seconds of silence between categories
+    #[arg(
+        long,
+        requires = "concat",
+        num_args = 0..=1,
+        default_missing_value = "2",
+        value_name = "SECS"
+    )]
+    pub render_silence_between_categories: Option<f64>,
+
+    /// Round each duration to the nearest sample instead of truncating, so files
+    /// are exactly round(sample_rate * duration) samples long
+    #[arg(long)]
+    pub exact_duration: bool,
+
+    /// Generate sines by phase accumulation (automatic for files over an hour)
+    #[arg(long)]
+    pub phase_accumulator: bool,
+
+    /// Write a manifest.sha256 listing every generated file's hash and size
+    #[arg(long)]
+    pub manifest: bool,
+
+    /// Also collect every generated file into this ZIP, keeping category subdirectories
+    #[arg(long, value_name = "FILE")]
+    pub archive: Option<PathBuf>,
+
+    /// Write a .cue sheet next to concatenated files
+    #[arg(long)]
+    pub cue_sheet: bool,
+
+    /// Print an ASCII spectrum of each generated file
+    #[arg(long)]
+    pub preview_spectrum: bool,
+
+    /// Widen mono output by delaying the right channel this many milliseconds (5-30)
+    #[arg(long, value_name = "MS", conflicts_with = "rotate")]
+    pub haas: Option<f64>,
+
+    /// Upmix mono output to mono-compatible stereo with an all-pass decorrelator (width 0-1)
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "0.5",
+        value_name = "WIDTH",
+        conflicts_with_all = ["rotate", "haas"]
+    )]
+    pub upmix: Option<f64>,
+
+    /// Write stereo output as two mono files, Mid (L+R) and Side (L-R)
+    #[arg(long)]
+    pub ms_split: bool,
+
+    /// Scale the Side (L-R) of stereo output: 0 = mono, 1 = unchanged, 2 = extra wide
+    #[arg(long, default_value = "1", value_name = "0..2")]
+    pub width: f64,
+
+    /// Downmix stereo output (binaural, panned, split layers) to a single channel
+    #[arg(long, conflicts_with_all = ["ms_split", "rotate", "haas", "upmix", "delay"])]
+    pub force_mono: bool,
+
+    /// Swap left and right on stereo output (for reversed headphones or a mirror image)
+    #[arg(long, conflicts_with = "ms_split")]
+    pub swap_channels: bool,
+
+    /// Rotate mono output around the stereo field this many times per second
+    #[arg(long)]
+    pub rotate: Option<f64>,
+
+    /// Add a stereo ping-pong delay with this echo time in seconds
+    #[arg(long)]
+    pub delay: Option<f64>,
+
+    /// Ping-pong delay feedback (capped at 0.95)
+    #[arg(long, default_value = "0.4")]
+    pub delay_feedback: f64,
+
+    /// Ping-pong delay wet mix (0-1)
+    #[arg(long, default_value = "0.3")]
+    pub delay_mix: f64,
+
+    /// Fraction of each isochronic pulse that is "on" (0-1)
+    #[arg(long, default_value = "0.5")]
+    pub duty: f64,
+
+    /// Reference pitch for A4 used when naming notes (Hz)
+    #[arg(long, default_value_t = DEFAULT_A4_HZ)]
+    pub tuning: f64,
+
+    /// Named tuning system for notes (overrides --tuning)
+    #[arg(long, conflicts_with = "tuning")]
+    pub tuning_system: Option<TuningSystem>,
+
+    /// Filename template for database frequencies
+    /// (placeholders: {prefix}, {name}, {hz}, {note})
+    #[arg(long, default_value = "{prefix}_{name}_{hz}hz")]
+    pub name_template: String,
+}
+
+/// Requested output sample rate
+#[derive(Debug, Clone, Copy)]
+pub enum SampleRate {
+    /// Pick the smallest standard rate for the highest generated frequency
+    Auto,
+    Fixed(u32),
 }
 
 #[derive(Subcommand)]
@@ -39,7 +285,11 @@ pub enum Commands {
 
     // Category commands (must match frequencies.toml)
     /// Generate all 9 Solfeggio frequencies
-    Solfeggio,
+    Solfeggio {
+        /// Also generate octave-doubled (x2, x4) variants and report digit roots
+        #[arg(long)]
+        extended: bool,
+    },
     /// Generate angel number frequencies (111, 222, 333, etc.)
     Angels,
     /// Generate chakra meditation sequence
@@ -175,22 +425,192 @@ pub enum Commands {
         /// Base carrier frequency
         #[arg(long, default_value = "200")]
         base: f64,
+        /// Point of each brainwave band to use as the beat frequency
+        #[arg(long, default_value = "mid")]
+        band_edge: BandEdge,
+    },
+    /// Generate each brainwave state's midpoint frequency as its own file
+    Brainwaves {
+        /// Generation mode
+        #[arg(long, default_value = "isochronic")]
+        mode: GenerationMode,
+    },
+    /// Generate a rising binaural beat sweep, with cue markers, to find your audible ceiling
+    BinauralCalibrate {
+        /// Base frequency for the left channel
+        #[arg(long, default_value = "200")]
+        base: f64,
+        /// Beat frequency at the start of the sweep
+        #[arg(long, default_value = "1")]
+        start: f64,
+        /// Beat frequency at the end of the sweep
+        #[arg(long, default_value = "40")]
+        end: f64,
+    },
+    /// Generate a binaural beat reinforced across several carriers
+    MultiBinaural {
+        /// Carrier frequencies for the left channel (comma-separated)
+        #[arg(long, value_delimiter = ',', default_value = "100,200,300")]
+        carriers: Vec<f64>,
+        /// Beat frequency added to each carrier on the right channel
+        #[arg(long, default_value = "7.83")]
+        beat: f64,
+    },
+    /// Generate a sleep track: a 10 Hz to 2 Hz binaural descent over a pink noise bed
+    SleepDescent {
+        /// Left-channel carrier frequency in Hz
+        #[arg(long, default_value = "200")]
+        base: f64,
+        /// Level of the pink noise bed (0 = none)
+        #[arg(long, default_value = "0.25")]
+        noise_level: f64,
+    },
+    /// Generate the Om tone (136.1 Hz with harmonics) as a binaural beat
+    OmBinaural {
+        /// Beat frequency added to every Om partial on the right channel
+        #[arg(long, default_value = "7.83")]
+        beat: f64,
+    },
+    /// Generate several binaural beats layered on one base carrier
+    MultiBeat {
+        /// Left-channel carrier frequency in Hz
+        #[arg(long, default_value = "200")]
+        base: f64,
+        /// Beat frequencies to layer (comma-separated)
+        #[arg(long, value_delimiter = ',', default_value = "4,7,10")]
+        beats: Vec<f64>,
+    },
+    /// Generate a category's tones together, spread across the stereo field
+    Panorama {
+        /// Category to place (e.g. chakras, solfeggio)
+        #[arg(value_parser = parse_category)]
+        category: Category,
+    },
+    /// Generate a category once per tuning reference, scaled from a baseline
+    Retune {
+        /// Category to retune (e.g. chakras, solfeggio)
+        #[arg(value_parser = parse_category)]
+        category: Category,
+        /// A4 references to render (comma-separated)
+        #[arg(long, value_delimiter = ',', default_value = "432,440,444")]
+        references: Vec<f64>,
+        /// A4 reference the database frequencies are assumed to use
+        #[arg(long, default_value_t = DEFAULT_A4_HZ)]
+        baseline: f64,
+    },
+    /// Compare two categories' frequencies and optionally generate their union or intersection
+    Diff {
+        /// First category
+        #[arg(value_parser = parse_category)]
+        first: Category,
+        /// Second category
+        #[arg(value_parser = parse_category)]
+        second: Category,
+        /// Generate this set of frequencies (by default only the report is printed)
+        #[arg(long)]
+        generate: Option<SetOperation>,
+    },
+    /// Generate a short clip of each category's first frequency
+    Sampler {
+        /// Seconds per clip
+        #[arg(long, default_value = "3")]
+        each: f64,
+    },
+    /// Generate one file previewing every category, with a named cue marker for each
+    AudioIndex {
+        /// Seconds of tone per category
+        #[arg(long, default_value = "2")]
+        each: f64,
+    },
+    /// Generate every database frequency within a tolerance of a target, from all categories
+    Near {
+        /// Target frequency in Hz
+        frequency: f64,
+        /// Maximum distance from the target in Hz
+        #[arg(long, default_value = "1")]
+        tolerance: f64,
     },
     /// Generate Schumann resonance (7.83 Hz)
     Schumann,
     /// Generate 432 Hz vs 440 Hz comparison
-    Tuning,
+    Tuning {
+        /// Play both tones at once instead of alternating them
+        #[arg(long)]
+        simultaneous: bool,
+    },
     /// Generate Om tone
     Om,
     /// Generate noise backgrounds
     Noise,
+    /// Generate noise amplitude-pulsed at an entrainment frequency
+    NoiseEntrainment {
+        /// Pulse (entrainment) frequency in Hz
+        #[arg(long, default_value = "10")]
+        beat: f64,
+        /// Noise color
+        #[arg(long, default_value = "pink")]
+        color: NoiseColor,
+    },
+    /// Generate fractional Brownian noise with a tunable Hurst exponent
+    Fnoise {
+        /// Hurst exponent between 0 and 1 (0.5 = brown, lower = brighter, higher = smoother)
+        #[arg(long, default_value = "0.5")]
+        hurst: f64,
+    },
+    /// Generate pink noise at a calibrated RMS level for room measurement
+    RoomPink {
+        /// RMS level in dBFS
+        #[arg(long, default_value = "-20", allow_negative_numbers = true)]
+        dbfs: f64,
+    },
+    /// Generate periodic noise bursts
+    NoiseBurst {
+        /// Noise color
+        #[arg(long, default_value = "pink")]
+        color: NoiseColor,
+        /// Seconds of noise per burst
+        #[arg(long, default_value = "1")]
+        on: f64,
+        /// Seconds of silence between bursts
+        #[arg(long, default_value = "1")]
+        off: f64,
+    },
+    /// Resample an existing WAV to --sample-rate and --bit-depth (-o may name the output file)
+    Convert {
+        /// WAV file to convert
+        input: PathBuf,
+        /// Interpolation used to change the sample rate
+        #[arg(long, default_value = "sinc")]
+        resampler: Resampler,
+    },
+    /// Generate an impulse, step, or linear chirp for validating a playback chain
+    TestSignal {
+        /// Signal to generate
+        #[arg(long = "type", default_value = "impulse")]
+        signal: TestSignal,
+        /// Chirp start frequency in Hz
+        #[arg(long, default_value = "20")]
+        start: f64,
+        /// Chirp end frequency in Hz
+        #[arg(long, default_value = "20000")]
+        end: f64,
+    },
     /// Generate a frequency sweep
     Sweep {
         /// Start frequency in Hz
-        #[arg(long, default_value = "20")]
+        #[arg(long, default_value = "20", allow_negative_numbers = true)]
         start: f64,
         /// End frequency in Hz
-        #[arg(long, default_value = "20000")]
+        #[arg(long, default_value = "20000", allow_negative_numbers = true)]
+        end: f64,
+    },
+    /// Generate a linear pitch glide
+    Glide {
+        /// Start frequency in Hz
+        #[arg(long, default_value = "100", allow_negative_numbers = true)]
+        start: f64,
+        /// End frequency in Hz
+        #[arg(long, default_value = "200", allow_negative_numbers = true)]
         end: f64,
     },
     /// Generate ambient drone
@@ -198,6 +618,34 @@ pub enum Commands {
         /// Frequencies to layer (comma-separated)
         #[arg(value_delimiter = ',')]
         frequencies: Vec<f64>,
+        /// Period in seconds of a slow amplitude swell over the whole drone
+        #[arg(long)]
+        swell: Option<f64>,
+        /// How far the swell dips below full level (0-1)
+        #[arg(long, default_value = "0.5")]
+        swell_depth: f64,
+        /// Sweep a low-pass filter over the drone at this LFO rate in Hz
+        #[arg(long)]
+        filter_sweep: Option<f64>,
+        /// Lowest cutoff of the filter sweep in Hz
+        #[arg(long, default_value = "200", requires = "filter_sweep")]
+        sweep_min: f64,
+        /// Highest cutoff of the filter sweep in Hz
+        #[arg(long, default_value = "2000", requires = "filter_sweep")]
+        sweep_max: f64,
+    },
+    /// Generate a drone stacked from a root and its octaves
+    OctaveDrone {
+        /// Root frequency in Hz
+        root: f64,
+        /// Number of octaves in the stack, including the root
+        #[arg(long, default_value = "4")]
+        octaves: u32,
+    },
+    /// Generate a named note (e.g. A4, C#3) under the selected tuning
+    Note {
+        /// Note name with octave
+        name: String,
     },
     /// Generate a custom frequency
     Custom {
@@ -206,28 +654,321 @@ pub enum Commands {
         /// Generation mode
         #[arg(long, default_value = "sine")]
         mode: GenerationMode,
+        /// Carrier in Hz for binaural and isochronic modes (default 200, raised for fast beats)
+        #[arg(long, conflicts_with = "auto_carrier")]
+        carrier: Option<f64>,
+        /// Pick the carrier as the musical note nearest 200 Hz (or ten times the beat)
+        #[arg(long)]
+        auto_carrier: bool,
+        /// Write the same tone to this many channels (e.g. 6 for a per-speaker test tone)
+        #[arg(long, value_parser = clap::value_parser!(u16).range(1..=32))]
+        channels: Option<u16>,
     },
     /// Generate layered frequencies
     Layer {
         /// Frequencies to layer (comma-separated)
         #[arg(value_delimiter = ',')]
         frequencies: Vec<f64>,
+        /// Route frequencies to alternating left/right channels instead of summing to mono
+        #[arg(long)]
+        split: bool,
+        /// Route frequencies round-robin across the speakers of this layout
+        #[arg(long, conflicts_with = "split")]
+        layout: Option<ChannelLayout>,
+    },
+    /// Generate log-spaced tones sounding together across a band (a frequency comb)
+    Comb {
+        /// Lowest tone in Hz
+        #[arg(long, default_value = "100")]
+        start: f64,
+        /// Highest tone in Hz
+        #[arg(long, default_value = "10000")]
+        end: f64,
+        /// Number of tones
+        #[arg(long, default_value = "20")]
+        count: usize,
+    },
+    /// Play a digit sequence (e.g. digits of pi) as a melody, one tone per digit
+    Sonify {
+        /// Digits to play; read from --file or stdin when omitted
+        sequence: Option<String>,
+        /// Read the digits from this file instead
+        #[arg(long, conflicts_with = "sequence")]
+        file: Option<PathBuf>,
+        /// Frequency of digit 0 in Hz
+        #[arg(long, default_value = "220")]
+        base: f64,
+        /// Hz added per digit step (negative to descend)
+        #[arg(long, default_value = "20", allow_negative_numbers = true)]
+        step: f64,
+        /// Length of each note in seconds
+        #[arg(long, default_value = "0.5")]
+        note_length: f64,
     },
+    /// Generate a frequency layered with its harmonic series
+    Harmonics {
+        /// Fundamental frequency in Hz
+        frequency: f64,
+        /// Number of harmonics above the fundamental
+        #[arg(long, default_value = "5")]
+        count: usize,
+        /// Stretch partial n to f*n*sqrt(1+B*n^2) for metallic, bell-like tones
+        #[arg(long, default_value = "0")]
+        inharmonicity: f64,
+    },
+    /// Generate a tone from a single-cycle wavetable (CSV or WAV)
+    Wavetable {
+        /// Path to the wavetable file
+        table: PathBuf,
+        /// Playback frequency in Hz
+        #[arg(long, default_value = "220")]
+        freq: f64,
+    },
+    /// Generate a two-tone pair demonstrating difference and sum tones
+    Intermod {
+        /// First frequency in Hz
+        #[arg(long, default_value = "1000")]
+        f1: f64,
+        /// Second frequency in Hz
+        #[arg(long, default_value = "1100")]
+        f2: f64,
+    },
+    /// Generate a just-intonation chord from ratios above a root
+    Intervals {
+        /// Root frequency in Hz
+        #[arg(long, default_value = "220")]
+        root: f64,
+        /// Frequency ratios to the root, as decimals or fractions (e.g. 1,3/2,5/4,2)
+        #[arg(long, value_delimiter = ',', value_parser = parse_ratio, default_value = "1,5/4,3/2")]
+        ratios: Vec<f64>,
+    },
+    /// Generate an isochronic pulse from a tempo instead of a frequency
+    Pulse {
+        /// Beats per minute
+        #[arg(long, default_value = "60")]
+        bpm: f64,
+        /// Pulses per beat (2 = eighths, 4 = sixteenths)
+        #[arg(long, default_value = "1")]
+        subdivision: u32,
+        /// Carrier frequency in Hz
+        #[arg(long, default_value = "200")]
+        carrier: f64,
+    },
+    /// Generate a tone whose waveform crossfades from one shape to another
+    Morph {
+        /// Frequency in Hz
+        frequency: f64,
+        /// Waveform at the start
+        #[arg(long, default_value = "sine")]
+        from: Waveform,
+        /// Waveform at the end
+        #[arg(long, default_value = "saw")]
+        to: Waveform,
+    },
+    /// Generate a tone together with the pitch twelve pure fifths arrive at
+    Comma {
+        /// Starting frequency in Hz
+        frequency: f64,
+    },
+    /// Generate a reference tone and a detuned copy that beat against each other
+    ZeroBeat {
+        /// Reference frequency in Hz
+        frequency: f64,
+        /// Offset of the second tone in Hz (the audible beat rate)
+        #[arg(long, default_value = "2", allow_negative_numbers = true)]
+        detune: f64,
+        /// Glide the second tone onto the reference so the beating slows to a stop
+        #[arg(long)]
+        ramp_to_zero: bool,
+    },
+    /// Render many files described by a JSON manifest
+    Batch {
+        /// Path to the JSON manifest
+        manifest: PathBuf,
+    },
+    /// Generate custom frequencies read from stdin, one "<hz> [mode]" per line
+    BatchStdin,
     /// Generate a singing bowl tone
     Bowl {
         /// Frequency in Hz
         frequency: f64,
+        /// Decay e-folding time in seconds (default: 70% of the duration)
+        #[arg(long)]
+        decay: Option<f64>,
+    },
+    /// Generate 40 Hz gamma entrainment audio
+    Gamma40 {
+        /// Carrier frequency in Hz
+        #[arg(long, default_value = "200")]
+        carrier: f64,
+        /// Generation mode
+        #[arg(long, default_value = "isochronic")]
+        mode: GenerationMode,
     },
     /// List all documented frequencies
     List,
+    /// Show every database entry matching a frequency or name, without generating audio
+    InfoFreq {
+        /// Frequency in Hz, or part of an entry name
+        query: String,
+        /// Print matches as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Recover the category, entry and frequency from a generated filename
+    ParseName {
+        /// Filename in the default template, e.g. solfeggio_528_528.00hz.wav
+        filename: String,
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the generation modes accepted by --mode
+    Modes,
+    /// Validate the compiled-in frequency database without generating audio
+    HeadlessCheck,
+    /// Benchmark generation speed and report the effective configuration
+    Doctor,
 }
 
 impl Commands {
+    /// Highest frequency this command will generate, if it is known
+    ///
+    /// Sub-audible database entries count as their 200 Hz isochronic carrier.
+    /// Returns `None` for broadband content such as noise.
+    pub fn max_frequency(&self) -> Option<f64> {
+        use Commands::*;
+
+        let rendered = |hz: f64| if hz < 20.0 { 200.0 } else { hz };
+        let category_max = |category: Category| {
+            category
+                .frequencies()
+                .iter()
+                .map(|f| rendered(f.hz))
+                .fold(0.0, f64::max)
+        };
+        let list_max = |freqs: &[f64]| freqs.iter().copied().fold(0.0, f64::max);
+
+        if let Some(category) = self.to_category() {
+            let octaves = if matches!(self, Solfeggio { extended: true }) {
+                4.0
+            } else {
+                1.0
+            };
+            return Some(category_max(category) * octaves);
+        }
+
+        match self {
+            Binaural { base, .. } => Some(base + 100.0),
+            Brainwaves { .. } => Some(300.0),
+            BinauralCalibrate { base, end, .. } => Some(base + end),
+            MultiBinaural { carriers, beat } => Some(list_max(carriers) + beat),
+            MultiBeat { base, beats } => Some(base + list_max(beats)),
+            OmBinaural { beat } => Some(OM_HZ * 3.0 + beat),
+            Panorama { category } => Some(category_max(*category)),
+            Diff { first, second, .. } => Some(category_max(*first).max(category_max(*second))),
+            Retune {
+                category,
+                references,
+                baseline,
+            } => Some(category_max(*category) * list_max(references) / baseline),
+            Sampler { .. } | AudioIndex { .. } => Some(
+                Category::all()
+                    .iter()
+                    .copied()
+                    .map(category_max)
+                    .fold(0.0, f64::max),
+            ),
+            Near {
+                frequency,
+                tolerance,
+            } => Some(rendered(frequency - tolerance).max(frequency + tolerance)),
+            Schumann => Some(207.83),
+            Tuning { .. } => Some(440.0),
+            Sweep { start, end } | Glide { start, end } => Some(start.max(*end)),
+            TestSignal {
+                signal: crate::generator::TestSignal::Chirp,
+                start,
+                end,
+            } => Some(start.max(*end)),
+            Drone { frequencies, .. } => Some(list_max(frequencies) * 1.01),
+            OctaveDrone { root, octaves } => Some(root * 2f64.powi(*octaves as i32 - 1) * 1.01),
+            Custom {
+                frequency,
+                mode,
+                carrier,
+                ..
+            } => Some(match mode {
+                GenerationMode::Sine => *frequency,
+                _ => {
+                    carrier.unwrap_or_else(|| (frequency * 10.0).max(DEFAULT_CARRIER_HZ))
+                        + frequency
+                }
+            }),
+            Layer { frequencies, .. } => Some(list_max(frequencies)),
+            Comb { start, end, .. } => Some(start.max(*end)),
+            Sonify { base, step, .. } => Some(base.max(base + 9.0 * step)),
+            Harmonics {
+                frequency,
+                count,
+                inharmonicity,
+            } => {
+                let n = *count as f64 + 1.0;
+                Some(frequency * n * (1.0 + inharmonicity.max(0.0) * n * n).sqrt())
+            }
+            Intermod { f1, f2 } => Some(f1.max(*f2)),
+            Intervals { root, ratios } => Some(root * list_max(ratios)),
+            Pulse {
+                bpm,
+                subdivision,
+                carrier,
+            } => Some(carrier + bpm / 60.0 * *subdivision as f64),
+            // Non-sine shapes are harmonically rich, so they need the default rate
+            Morph {
+                frequency,
+                from: Waveform::Sine,
+                to: Waveform::Sine,
+            } => Some(*frequency),
+            Comma { frequency } => Some(frequency * PYTHAGOREAN_COMMA),
+            ZeroBeat {
+                frequency, detune, ..
+            } => Some(frequency + detune.max(0.0)),
+            Bowl { frequency, .. } => Some(frequency * 5.12),
+            Gamma40 { carrier, .. } => Some(carrier + 40.0),
+            _ => None,
+        }
+    }
+
+    /// Most channels any file from this command will have, before global options
+    pub fn output_channels(&self) -> u16 {
+        use Commands::*;
+        match self {
+            All
+            | Binaural { .. }
+            | BinauralCalibrate { .. }
+            | MultiBinaural { .. }
+            | MultiBeat { .. }
+            | OmBinaural { .. }
+            | SleepDescent { .. }
+            | Panorama { .. }
+            | Schumann
+            | Batch { .. } => 2,
+            Custom {
+                channels: Some(channels),
+                ..
+            } => *channels,
+            Brainwaves { mode } | Custom { mode, .. } | Gamma40 { mode, .. } => mode.channels(),
+            Layer { split: true, .. } => 2,
+            Layer { layout, .. } => layout.map_or(1, ChannelLayout::channels),
+            _ => 1,
+        }
+    }
+
     /// Map command to category if applicable
     pub fn to_category(&self) -> Option<Category> {
         use Commands::*;
         match self {
-            Solfeggio => Some(Category::Solfeggio),
+            Solfeggio { .. } => Some(Category::Solfeggio),
             Angels => Some(Category::Angels),
             Chakras => Some(Category::Chakras),
             Planets => Some(Category::Planetary),
@@ -298,7 +1039,7 @@ impl Commands {
 }
 
 /// Print all documented frequencies
-pub fn print_frequency_list() {
+pub fn print_frequency_list(a4_ref: f64) {
     println!("\n{}", "=".repeat(70));
     println!("DOCUMENTED FREQUENCIES DATABASE");
     println!("{}\n", "=".repeat(70));
@@ -322,8 +1063,302 @@ pub fn print_frequency_list() {
             if f.hz == 0.0 {
                 println!("  {:>7} Hz: {} - {}", "N/A", f.name, f.description);
             } else {
-                println!("  {:>7.2} Hz: {}", f.hz, f.description);
+                let (note, cents) = hz_to_note(f.hz, a4_ref);
+                println!(
+                    "  {:>7.2} Hz [{:>4} {:>+6.1}c]: {}",
+                    f.hz, note, cents, f.description
+                );
+            }
+        }
+    }
+}
+
+/// Print every database entry matching `query`, by Hz or case-insensitive name
+///
+/// Numeric queries match entries within 0.005 Hz. Returns the number of matches.
+pub fn print_frequency_info(query: &str, json: bool, a4_ref: f64) -> usize {
+    let query = query.trim();
+    let matches = frequency_matches(query);
+
+    if json {
+        let entries: Vec<_> = matches
+            .iter()
+            .map(|(category, f)| {
+                serde_json::json!({
+                    "category": category.dir_name(),
+                    "name": f.name,
+                    "hz": f.hz,
+                    "description": f.description,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&entries).expect("JSON values always serialize")
+        );
+        return matches.len();
+    }
+
+    println!("\n--- {} Match(es) for \"{}\" ---", matches.len(), query);
+    for (category, f) in &matches {
+        if f.hz == 0.0 {
+            println!(
+                "  [{}] {:>7} Hz: {} - {}",
+                category.display_name(),
+                "N/A",
+                f.name,
+                f.description
+            );
+        } else {
+            let (note, cents) = hz_to_note(f.hz, a4_ref);
+            println!(
+                "  [{}] {:>7.2} Hz [{:>4} {:>+6.1}c]: {} - {}",
+                category.display_name(),
+                f.hz,
+                note,
+                cents,
+                f.name,
+                f.description
+            );
+        }
+    }
+    matches.len()
+}
+
+/// Database entries matching `query`, by Hz or case-insensitive name, with their category
+fn frequency_matches(query: &str) -> Vec<(Category, &'static FrequencyInfo)> {
+    let hz = query.parse::<f64>().ok();
+    let needle = query.to_lowercase();
+    Category::all()
+        .iter()
+        .flat_map(|&category| category.frequencies().iter().map(move |f| (category, f)))
+        .filter(|(_, f)| match hz {
+            Some(hz) => (f.hz - hz).abs() < 0.005,
+            None => f.name.to_lowercase().contains(&needle),
+        })
+        .collect()
+}
+
+/// Print the database entry a generated filename came from
+///
+/// Returns `false` when the name does not match the default template.
+pub fn print_parsed_name(filename: &str, json: bool) -> bool {
+    let Some((category, f)) = parse_frequency_filename(filename) else {
+        return false;
+    };
+
+    if json {
+        let entry = serde_json::json!({
+            "category": category.dir_name(),
+            "name": f.name,
+            "hz": f.hz,
+            "description": f.description,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&entry).expect("JSON values always serialize")
+        );
+    } else {
+        println!("\n--- {} ---", filename);
+        println!(
+            "  Category:    {} ({})",
+            category.display_name(),
+            category.dir_name()
+        );
+        println!("  Name:        {}", f.name);
+        println!("  Frequency:   {:.2} Hz", f.hz);
+        println!("  Description: {}", f.description);
+    }
+    true
+}
+
+/// Print every generation mode with its channel count and description
+pub fn print_modes() {
+    println!("\n--- Generation Modes ---");
+    for line in mode_listing() {
+        println!("{}", line);
+    }
+}
+
+/// One line per generation mode: name, channel count, and description
+fn mode_listing() -> Vec<String> {
+    GenerationMode::value_variants()
+        .iter()
+        .filter_map(|mode| {
+            // Names and descriptions come from the enum's doc comments via clap
+            let value = mode.to_possible_value()?;
+            let channels = if mode.channels() == 1 {
+                "mono"
+            } else {
+                "stereo"
+            };
+            let help = value
+                .get_help()
+                .map(ToString::to_string)
+                .unwrap_or_default();
+            Some(format!(
+                "  {:<11} {:<7} {}",
+                value.get_name(),
+                channels,
+                help
+            ))
+        })
+        .collect()
+}
+
+/// Check every category for invalid frequencies, returning the issue count
+pub fn run_headless_check() -> usize {
+    let mut total = 0;
+    let mut checked = 0;
+
+    for category in Category::all() {
+        let issues = frequency_issues(category.frequencies());
+        checked += category.frequencies().len();
+        if !issues.is_empty() {
+            println!("--- {} ---", category.display_name());
+            for issue in &issues {
+                println!("  {}", issue);
             }
+            total += issues.len();
+        }
+    }
+
+    println!(
+        "Checked {} frequencies across {} categories: {} issue(s)",
+        checked,
+        Category::all().len(),
+        total
+    );
+    total
+}
+
+/// Parse a category by its subcommand or directory name
+fn parse_category(s: &str) -> Result<Category, String> {
+    Category::all()
+        .iter()
+        .copied()
+        .find(|c| c.dir_name() == s)
+        .or_else(|| {
+            Cli::try_parse_from(["spirit", s])
+                .ok()
+                .and_then(|cli| cli.command.to_category())
+        })
+        .ok_or_else(|| format!("unknown category: {}", s))
+}
+
+/// Parse a sample rate in Hz, or `auto`
+fn parse_sample_rate(s: &str) -> Result<SampleRate, String> {
+    if s.eq_ignore_ascii_case("auto") {
+        return Ok(SampleRate::Auto);
+    }
+    s.parse()
+        .map(SampleRate::Fixed)
+        .map_err(|_| format!("invalid sample rate: {}", s))
+}
+
+/// Parse a ratio written as a decimal (`1.5`) or a fraction (`3/2`)
+fn parse_ratio(s: &str) -> Result<f64, String> {
+    let parse = |part: &str| {
+        part.trim()
+            .parse::<f64>()
+            .map_err(|_| format!("invalid ratio: {}", s))
+    };
+    match s.split_once('/') {
+        Some((num, den)) => Ok(parse(num)? / parse(den)?),
+        None => parse(s),
+    }
+}
+
+/// Expand placeholders in the output directory template
+///
+/// - `{date}`: local date as `YYYY-MM-DD`
+/// - `{time}`: local time as `HHMMSS`
+/// - `{category}`: category directory name, or the subcommand name
+/// - `{run}`: lowest three-digit run number whose directory does not exist yet
+pub fn expand_output_template(template: &Path, category: &str) -> PathBuf {
+    let now = chrono::Local::now();
+    let expanded = template
+        .to_string_lossy()
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H%M%S").to_string())
+        .replace("{category}", category);
+
+    if !expanded.contains("{run}") {
+        return PathBuf::from(expanded);
+    }
+
+    (1..)
+        .map(|run| PathBuf::from(expanded.replace("{run}", &format!("{:03}", run))))
+        .find(|path| !path.exists())
+        .expect("run numbers are unbounded")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AudioConfig;
+    use crate::generator::{AudioGenerator, GenerationMode, DEFAULT_CARRIER_HZ};
+    use crate::test_support;
+
+    #[test]
+    fn output_template_expands_date_and_category() {
+        let root = test_support::temp_dir("template");
+        let output = expand_output_template(&root.join("{date}/{category}"), "custom");
+
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(output, root.join(&today).join("custom"));
+
+        let gen = AudioGenerator::new(output.clone(), 0.5, AudioConfig::default());
+        gen.generate_custom(528.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ)
+            .unwrap();
+        assert!(output.join("custom_528.00hz_sine.wav").exists());
+    }
+
+    #[test]
+    fn output_template_picks_the_next_free_run() {
+        let root = test_support::temp_dir("template-run");
+        std::fs::create_dir_all(root.join("run_001")).unwrap();
+        assert_eq!(
+            expand_output_template(&root.join("run_{run}"), "custom"),
+            root.join("run_002")
+        );
+    }
+
+    #[test]
+    fn mode_listing_covers_every_mode() {
+        let listing = mode_listing();
+        assert_eq!(listing.len(), GenerationMode::value_variants().len());
+        for (line, mode) in listing.iter().zip(GenerationMode::value_variants()) {
+            let name = mode.to_possible_value().unwrap();
+            assert!(line.trim_start().starts_with(name.get_name()), "{}", line);
+            assert!(line.contains("mono") || line.contains("stereo"));
         }
     }
+
+    #[test]
+    fn info_freq_528_matches_entries_across_categories() {
+        let matches = frequency_matches("528");
+        assert!(matches.len() > 1);
+        assert!(matches.iter().all(|(_, f)| f.hz == 528.0));
+
+        let description = |category: Category| {
+            matches
+                .iter()
+                .find(|(c, _)| *c == category)
+                .map(|(_, f)| f.description)
+        };
+        assert_eq!(
+            description(Category::Solfeggio),
+            Some("Love frequency, DNA repair, miracles")
+        );
+        assert_eq!(
+            description(Category::Chakras),
+            Some("Solar plexus (Manipura) - confidence")
+        );
+
+        assert_eq!(
+            print_frequency_info("528", true, DEFAULT_A4_HZ),
+            matches.len()
+        );
+    }
 }
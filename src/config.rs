@@ -1,5 +1,9 @@
 /// Default audio sample rate (CD quality)
 pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+/// Standard sample rates, in ascending order
+pub const STANDARD_SAMPLE_RATES: [u32; 6] = [44100, 48000, 88200, 96000, 176400, 192000];
+/// Sample rate needed per Hz of the highest frequency (Nyquist at 2.5x)
+const SAMPLE_RATE_HEADROOM: f64 = 5.0;
 /// Default bit depth
 pub const DEFAULT_BIT_DEPTH: u16 = 16;
 /// Default amplitude (leaves headroom to prevent clipping)
@@ -10,6 +14,8 @@ pub const AMPLITUDE: f64 = 0.8;
 pub struct AudioConfig {
     pub sample_rate: u32,
     pub bit_depth: u16,
+    /// Write IEEE float samples instead of integer PCM
+    pub float: bool,
 }
 
 impl Default for AudioConfig {
@@ -17,6 +23,40 @@ impl Default for AudioConfig {
         Self {
             sample_rate: DEFAULT_SAMPLE_RATE,
             bit_depth: DEFAULT_BIT_DEPTH,
+            float: false,
         }
     }
 }
+
+impl AudioConfig {
+    /// Estimated WAV file size in bytes for the given duration and channel count
+    pub fn estimated_wav_bytes(&self, duration_secs: f64, channels: u16) -> u64 {
+        let frames = (self.sample_rate as f64 * duration_secs) as u64;
+        let bytes_per_sample = (self.bit_depth as u64).div_ceil(8);
+        44 + frames * channels as u64 * bytes_per_sample
+    }
+}
+
+/// Smallest standard sample rate with headroom above `max_freq`
+///
+/// Never goes below the CD-quality default, and caps at the highest standard rate.
+pub fn min_sample_rate_for(max_freq: f64) -> u32 {
+    let needed = max_freq * SAMPLE_RATE_HEADROOM;
+    STANDARD_SAMPLE_RATES
+        .iter()
+        .copied()
+        .find(|&rate| rate as f64 >= needed)
+        .unwrap_or(STANDARD_SAMPLE_RATES[STANDARD_SAMPLE_RATES.len() - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_sample_rate_scales_with_the_top_frequency() {
+        assert_eq!(min_sample_rate_for(528.0), 44100);
+        assert_eq!(min_sample_rate_for(10_000.0), 88200);
+        assert_eq!(min_sample_rate_for(100_000.0), 192000);
+    }
+}
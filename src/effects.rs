@@ -0,0 +1,927 @@
+//! Post-processing effects and the `--fx` chain parser.
+//!
+//! Effects operate in place on a single channel of f64 samples. A chain is
+//! written as comma-separated `name:value` pairs, e.g.
+//! `lowpass:2000,reverb:0.3,normalize:0.95`, and applied in order.
+
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+/// A single effect with its parameter
+#[derive(Debug, Clone, Copy)]
+pub enum Effect {
+    /// Low-pass filter at the given cutoff (Hz)
+    Lowpass(f64),
+    /// High-pass filter at the given cutoff (Hz)
+    Highpass(f64),
+    /// Reverb with the given wet mix (0-1)
+    Reverb(f64),
+    /// Tremolo at the given rate (Hz)
+    Tremolo(f64),
+    /// Normalize to the given peak level (0-1)
+    Normalize(f64),
+    /// Peak limiter with the given ceiling (0-1)
+    Limiter(f64),
+    /// Bit crusher with the given effective bits and sample-hold factor
+    Bitcrush(u32, usize),
+    /// Soft tanh saturation with the given drive
+    Saturation(f64),
+}
+
+impl Effect {
+    /// Parse a single `name[:value]` effect, range-checking the value for `sample_rate`
+    fn parse(spec: &str, sample_rate: u32) -> Result<Self, String> {
+        let (name, raw, value) = match spec.split_once(':') {
+            Some((name, raw)) => {
+                let value = raw
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(|| format!("invalid value for effect '{}': {}", name, raw))?;
+                (name.trim(), raw.trim(), Some(value))
+            }
+            None => (spec.trim(), "", None),
+        };
+
+        let nyquist = sample_rate as f64 / 2.0;
+        let (effect, valid) = match name {
+            "lowpass" | "highpass" => {
+                let default = if name == "lowpass" { 2000.0 } else { 80.0 };
+                let cutoff = value.unwrap_or(default);
+                let effect = if name == "lowpass" {
+                    Effect::Lowpass(cutoff)
+                } else {
+                    Effect::Highpass(cutoff)
+                };
+                (effect, cutoff > 0.0 && cutoff < nyquist)
+            }
+            "reverb" => {
+                let wet = value.unwrap_or(0.3);
+                (Effect::Reverb(wet), (0.0..=1.0).contains(&wet))
+            }
+            "tremolo" => {
+                let rate = value.unwrap_or(4.0);
+                (Effect::Tremolo(rate), rate > 0.0 && rate < nyquist)
+            }
+            "normalize" => {
+                let peak = value.unwrap_or(0.95);
+                (Effect::Normalize(peak), (0.0..=1.0).contains(&peak))
+            }
+            "limiter" => {
+                let ceiling = value.unwrap_or(0.9);
+                (Effect::Limiter(ceiling), (0.0..=1.0).contains(&ceiling))
+            }
+            "bitcrush" => {
+                let bits = value.unwrap_or(8.0);
+                (
+                    Effect::Bitcrush(bits as u32, 1),
+                    (1.0..=24.0).contains(&bits) && bits.fract() == 0.0,
+                )
+            }
+            "saturate" => {
+                let drive = value.unwrap_or(2.0);
+                (Effect::Saturation(drive), drive > 0.0)
+            }
+            _ => return Err(format!("unknown effect: {}", name)),
+        };
+
+        if !valid {
+            return Err(format!("invalid value for effect '{}': {}", name, raw));
+        }
+        Ok(effect)
+    }
+
+    /// Apply this effect to one channel in place
+    pub fn apply(self, samples: &mut [f64], sample_rate: u32) {
+        match self {
+            Effect::Lowpass(cutoff) => Biquad::lowpass(sample_rate, cutoff).process(samples),
+            Effect::Highpass(cutoff) => Biquad::highpass(sample_rate, cutoff).process(samples),
+            Effect::Reverb(wet) => apply_reverb(samples, sample_rate, wet),
+            Effect::Tremolo(rate) => apply_tremolo(samples, sample_rate, rate, 0.5),
+            Effect::Normalize(peak) => normalize(samples, peak),
+            Effect::Limiter(ceiling) => apply_limiter(samples, sample_rate, ceiling),
+            Effect::Bitcrush(bits, hold) => apply_bitcrush(samples, bits, hold),
+            Effect::Saturation(drive) => apply_saturation(samples, drive),
+        }
+    }
+}
+
+/// Parse a comma-separated effects chain for output at `sample_rate`
+///
+/// Values must be finite and in range: cutoffs and tremolo rates between 0
+/// and Nyquist, reverb, normalize and limiter levels in 0-1, bitcrush bits a
+/// whole number from 1 to 24, and saturation drive above 0.
+pub fn parse_chain(spec: &str, sample_rate: u32) -> Result<Vec<Effect>, String> {
+    spec.split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| Effect::parse(part, sample_rate))
+        .collect()
+}
+
+/// Apply each effect in the chain, in order
+pub fn apply_chain(samples: &mut [f64], chain: &[Effect], sample_rate: u32) {
+    for effect in chain {
+        effect.apply(samples, sample_rate);
+    }
+}
+
+/// Apply the chain at twice the sample rate, then band-limit back down
+///
+/// Nonlinear effects such as saturation and bit crushing create harmonics
+/// above Nyquist; at the doubled rate those land in the band the decimation
+/// filter removes instead of folding back onto the audible range.
+pub fn apply_chain_oversampled(samples: &mut [f64], chain: &[Effect], sample_rate: u32) {
+    let mut upsampled = upsample_2x(samples, sample_rate);
+    apply_chain(&mut upsampled, chain, sample_rate * 2);
+    let downsampled = downsample_2x(&upsampled, sample_rate);
+    samples.copy_from_slice(&downsampled);
+}
+
+/// Double the sample rate by zero-stuffing and removing the spectral images
+pub fn upsample_2x(samples: &[f64], sample_rate: u32) -> Vec<f64> {
+    let mut upsampled: Vec<f64> = samples.iter().flat_map(|&s| [2.0 * s, 0.0]).collect();
+    halfband_lowpass(&mut upsampled, sample_rate);
+    upsampled
+}
+
+/// Halve the sample rate of 2x-oversampled audio, filtering out what would alias
+///
+/// `sample_rate` is the target rate, half that of `samples`.
+pub fn downsample_2x(samples: &[f64], sample_rate: u32) -> Vec<f64> {
+    let mut filtered = samples.to_vec();
+    halfband_lowpass(&mut filtered, sample_rate);
+    filtered.into_iter().step_by(2).collect()
+}
+
+/// 8th-order Butterworth low-pass at 45% of `sample_rate`, run at twice that rate
+fn halfband_lowpass(samples: &mut [f64], sample_rate: u32) {
+    // Pole-pair Qs of an 8th-order Butterworth response
+    const STAGE_Q: [f64; 4] = [0.5098, 0.6013, 0.9000, 2.5629];
+    let cutoff = sample_rate as f64 * 0.45;
+    for q in STAGE_Q {
+        Biquad::lowpass_q(sample_rate * 2, cutoff, q).process(samples);
+    }
+}
+
+/// Second-order IIR filter (RBJ audio EQ cookbook)
+#[derive(Debug, Clone, Copy)]
+pub struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    /// Butterworth-Q low-pass filter
+    pub fn lowpass(sample_rate: u32, cutoff: f64) -> Self {
+        Self::lowpass_q(sample_rate, cutoff, FRAC_1_SQRT_2)
+    }
+
+    /// Low-pass filter with an explicit Q
+    pub fn lowpass_q(sample_rate: u32, cutoff: f64, q: f64) -> Self {
+        let (cos_w, alpha) = Self::prewarp(sample_rate, cutoff, q);
+        let b1 = 1.0 - cos_w;
+        Self::normalized(
+            b1 / 2.0,
+            b1,
+            b1 / 2.0,
+            1.0 + alpha,
+            -2.0 * cos_w,
+            1.0 - alpha,
+        )
+    }
+
+    /// Butterworth-Q high-pass filter
+    pub fn highpass(sample_rate: u32, cutoff: f64) -> Self {
+        Self::highpass_q(sample_rate, cutoff, FRAC_1_SQRT_2)
+    }
+
+    /// High-pass filter with an explicit Q
+    pub fn highpass_q(sample_rate: u32, cutoff: f64, q: f64) -> Self {
+        let (cos_w, alpha) = Self::prewarp(sample_rate, cutoff, q);
+        let b1 = -(1.0 + cos_w);
+        Self::normalized(
+            -b1 / 2.0,
+            b1,
+            -b1 / 2.0,
+            1.0 + alpha,
+            -2.0 * cos_w,
+            1.0 - alpha,
+        )
+    }
+
+    /// High-shelf filter boosting (or cutting) above `cutoff` by `gain_db`
+    pub fn high_shelf(sample_rate: u32, cutoff: f64, gain_db: f64, q: f64) -> Self {
+        let (cos_w, alpha) = Self::prewarp(sample_rate, cutoff, q);
+        let a = 10f64.powf(gain_db / 40.0);
+        let root = 2.0 * a.sqrt() * alpha;
+        Self::normalized(
+            a * ((a + 1.0) + (a - 1.0) * cos_w + root),
+            -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w),
+            a * ((a + 1.0) + (a - 1.0) * cos_w - root),
+            (a + 1.0) - (a - 1.0) * cos_w + root,
+            2.0 * ((a - 1.0) - (a + 1.0) * cos_w),
+            (a + 1.0) - (a - 1.0) * cos_w - root,
+        )
+    }
+
+    /// Filter a single sample
+    pub fn tick(&mut self, x: f64) -> f64 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+
+    /// Filter a buffer in place
+    pub fn process(mut self, samples: &mut [f64]) {
+        for sample in samples.iter_mut() {
+            *sample = self.tick(*sample);
+        }
+    }
+
+    fn prewarp(sample_rate: u32, cutoff: f64, q: f64) -> (f64, f64) {
+        let nyquist = sample_rate as f64 / 2.0;
+        let w = 2.0 * PI * cutoff.clamp(1.0, nyquist * 0.99) / sample_rate as f64;
+        (w.cos(), w.sin() / (2.0 * q))
+    }
+
+    fn normalized(b0: f64, b1: f64, b2: f64, a0: f64, a1: f64, a2: f64) -> Self {
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+}
+
+/// Low-pass filter whose cutoff an LFO sweeps between two frequencies (auto-wah)
+///
+/// The cutoff moves on a log scale so the sweep sounds even, starting at
+/// `min_cutoff` and peaking at `max_cutoff` once per LFO cycle. Coefficients
+/// are recomputed every few samples while the filter state carries over.
+pub fn apply_filter_sweep(
+    samples: &mut [f64],
+    sample_rate: u32,
+    min_cutoff: f64,
+    max_cutoff: f64,
+    rate: f64,
+) {
+    const UPDATE_INTERVAL: usize = 32;
+
+    let mut filter = Biquad::lowpass(sample_rate, min_cutoff);
+    for (block_idx, block) in samples.chunks_mut(UPDATE_INTERVAL).enumerate() {
+        let t = (block_idx * UPDATE_INTERVAL) as f64 / sample_rate as f64;
+        let lfo = 0.5 * (1.0 - (2.0 * PI * rate * t).cos());
+        let cutoff = min_cutoff * (max_cutoff / min_cutoff).powf(lfo);
+        filter = Biquad {
+            z1: filter.z1,
+            z2: filter.z2,
+            ..Biquad::lowpass(sample_rate, cutoff)
+        };
+        for sample in block.iter_mut() {
+            *sample = filter.tick(*sample);
+        }
+    }
+}
+
+/// Attack/decay/sustain/release amplitude envelope (times in seconds)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adsr {
+    pub attack: f64,
+    pub decay: f64,
+    /// Level held between the decay and the release (0-1)
+    pub sustain: f64,
+    pub release: f64,
+}
+
+impl Adsr {
+    /// Gain at sample `i` of a `len`-sample note
+    ///
+    /// When the stages are longer than the note they are shortened in
+    /// proportion, so short files still get the whole shape.
+    pub fn gain(&self, i: usize, len: usize, sample_rate: u32) -> f64 {
+        let rate = sample_rate as f64;
+        let total = (self.attack + self.decay + self.release) * rate;
+        let scale = if total > len as f64 {
+            len as f64 / total
+        } else {
+            1.0
+        };
+        let attack = self.attack * rate * scale;
+        let decay = self.decay * rate * scale;
+        let release = self.release * rate * scale;
+        let sustain = self.sustain.clamp(0.0, 1.0);
+
+        let level_before_release = |pos: f64| {
+            if pos < attack {
+                pos / attack
+            } else if pos < attack + decay {
+                1.0 - (1.0 - sustain) * (pos - attack) / decay
+            } else {
+                sustain
+            }
+        };
+
+        let pos = i as f64;
+        let release_start = len as f64 - release;
+        if pos < release_start {
+            level_before_release(pos)
+        } else {
+            level_before_release(release_start) * (len as f64 - pos) / release
+        }
+    }
+
+    /// Shape a buffer in place
+    pub fn apply(&self, samples: &mut [f64], sample_rate: u32) {
+        let len = samples.len();
+        for (i, sample) in samples.iter_mut().enumerate() {
+            *sample *= self.gain(i, len, sample_rate);
+        }
+    }
+}
+
+/// Named ADSR shapes for `--envelope-preset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EnvelopePreset {
+    /// Slow 2 s attack, 1 s decay to 80%, 3 s release
+    Pad,
+    /// 5 ms attack, 400 ms decay to silence, 100 ms release
+    Pluck,
+    /// 8 s attack straight to full level, 4 s release
+    Swell,
+    /// Hard 5 ms on/off edges at full level
+    Gate,
+}
+
+impl EnvelopePreset {
+    /// The ADSR values this preset stands for
+    pub fn adsr(self) -> Adsr {
+        let (attack, decay, sustain, release) = match self {
+            EnvelopePreset::Pad => (2.0, 1.0, 0.8, 3.0),
+            EnvelopePreset::Pluck => (0.005, 0.4, 0.0, 0.1),
+            EnvelopePreset::Swell => (8.0, 0.0, 1.0, 4.0),
+            EnvelopePreset::Gate => (0.005, 0.0, 1.0, 0.005),
+        };
+        Adsr {
+            attack,
+            decay,
+            sustain,
+            release,
+        }
+    }
+}
+
+/// Schroeder reverb: parallel feedback combs into series all-passes
+pub fn apply_reverb(samples: &mut [f64], sample_rate: u32, wet: f64) {
+    const COMB_MS: [f64; 4] = [29.7, 37.1, 41.1, 43.7];
+    const ALLPASS_MS: [f64; 2] = [5.0, 1.7];
+    const COMB_FEEDBACK: f64 = 0.84;
+    const ALLPASS_GAIN: f64 = 0.7;
+
+    let wet = wet.clamp(0.0, 1.0);
+    let delay_len = |ms: f64| ((ms / 1000.0 * sample_rate as f64) as usize).max(1);
+
+    let mut combs: Vec<(Vec<f64>, usize)> = COMB_MS
+        .iter()
+        .map(|&ms| (vec![0.0; delay_len(ms)], 0))
+        .collect();
+    let mut allpasses: Vec<(Vec<f64>, usize)> = ALLPASS_MS
+        .iter()
+        .map(|&ms| (vec![0.0; delay_len(ms)], 0))
+        .collect();
+
+    for sample in samples.iter_mut() {
+        let dry = *sample;
+
+        let mut out = 0.0;
+        for (buffer, pos) in combs.iter_mut() {
+            let delayed = buffer[*pos];
+            buffer[*pos] = dry + delayed * COMB_FEEDBACK;
+            *pos = (*pos + 1) % buffer.len();
+            out += delayed;
+        }
+        out /= COMB_MS.len() as f64;
+
+        for (buffer, pos) in allpasses.iter_mut() {
+            let delayed = buffer[*pos];
+            let input = out + delayed * ALLPASS_GAIN;
+            buffer[*pos] = input;
+            *pos = (*pos + 1) % buffer.len();
+            out = delayed - input * ALLPASS_GAIN;
+        }
+
+        *sample = dry * (1.0 - wet) + out * wet;
+    }
+}
+
+/// Sinusoidal amplitude modulation with the given depth (0-1)
+pub fn apply_tremolo(samples: &mut [f64], sample_rate: u32, rate: f64, depth: f64) {
+    let depth = depth.clamp(0.0, 1.0);
+    for (i, sample) in samples.iter_mut().enumerate() {
+        let t = i as f64 / sample_rate as f64;
+        let lfo = 0.5 * (1.0 + (2.0 * PI * rate * t).sin());
+        *sample *= 1.0 - depth * lfo;
+    }
+}
+
+/// Scale so the largest absolute sample equals `peak`
+pub fn normalize(samples: &mut [f64], peak: f64) {
+    let max = samples.iter().fold(0.0f64, |acc, s| acc.max(s.abs()));
+    if max > 0.0 {
+        let gain = peak / max;
+        for sample in samples.iter_mut() {
+            *sample *= gain;
+        }
+    }
+}
+
+/// Scale so the RMS level equals `target_rms`
+pub fn normalize_rms(samples: &mut [f64], target_rms: f64) {
+    let rms = crate::analysis::rms(samples);
+    if rms > 0.0 {
+        let gain = target_rms / rms;
+        for sample in samples.iter_mut() {
+            *sample *= gain;
+        }
+    }
+}
+
+/// Peak limiter with instant attack and a 50 ms release
+pub fn apply_limiter(samples: &mut [f64], sample_rate: u32, ceiling: f64) {
+    let release = (-1.0 / (0.05 * sample_rate as f64)).exp();
+    let mut gain = 1.0f64;
+
+    for sample in samples.iter_mut() {
+        let level = sample.abs();
+        let target = if level > ceiling {
+            ceiling / level
+        } else {
+            1.0
+        };
+        gain = if target < gain {
+            target
+        } else {
+            target + (gain - target) * release
+        };
+        *sample *= gain;
+    }
+}
+
+/// Quantize to `bits` effective bits, holding each result for `sample_rate_reduction` samples
+///
+/// Levels are spaced like signed PCM, so 4 bits leaves at most 16 distinct
+/// values. A reduction of 1 keeps every sample; larger factors repeat each
+/// kept sample, aliasing the signal the way a lower sample rate would.
+pub fn apply_bitcrush(samples: &mut [f64], bits: u32, sample_rate_reduction: usize) {
+    let half = 2f64.powi(bits.clamp(1, 32) as i32 - 1);
+    let hold = sample_rate_reduction.max(1);
+
+    let mut held = 0.0;
+    for (i, sample) in samples.iter_mut().enumerate() {
+        if i % hold == 0 {
+            held = (*sample * half).round().clamp(-half, half - 1.0) / half;
+        }
+        *sample = held;
+    }
+}
+
+/// Soft-clip through a `tanh` curve, adding harmonics like driven analog gear
+///
+/// A small bias makes the curve asymmetric, as in a tube stage, so even
+/// harmonics appear alongside the odd ones; the resulting DC offset is
+/// removed. The result is scaled back to the input's RMS level so raising
+/// the drive changes the tone rather than the loudness.
+pub fn apply_saturation(samples: &mut [f64], drive: f64) {
+    const BIAS: f64 = 0.2;
+    if drive <= 0.0 || samples.is_empty() {
+        return;
+    }
+    let input_rms = crate::analysis::rms(samples);
+    for sample in samples.iter_mut() {
+        *sample = (*sample * drive + BIAS).tanh() - BIAS.tanh();
+    }
+    let offset = samples.iter().sum::<f64>() / samples.len() as f64;
+    for sample in samples.iter_mut() {
+        *sample -= offset;
+    }
+    normalize_rms(samples, input_rms);
+}
+
+/// Peak envelope of the samples with separate attack and release times
+pub fn envelope_follower(
+    samples: &[f64],
+    sample_rate: u32,
+    attack_secs: f64,
+    release_secs: f64,
+) -> Vec<f64> {
+    let coefficient = |secs: f64| (-1.0 / (secs * sample_rate as f64)).exp();
+    let (attack, release) = (coefficient(attack_secs), coefficient(release_secs));
+
+    let mut envelope = 0.0f64;
+    samples
+        .iter()
+        .map(|sample| {
+            let level = sample.abs();
+            let coeff = if level > envelope { attack } else { release };
+            envelope = level + (envelope - level) * coeff;
+            envelope
+        })
+        .collect()
+}
+
+/// Rotate a mono signal between the speakers `rate` times per second
+///
+/// The pan position follows a sine from center to right, back through
+/// center to left, with equal-power gains so loudness stays constant.
+pub fn apply_rotation(samples: &[f64], sample_rate: u32, rate: f64) -> Vec<[f64; 2]> {
+    samples
+        .iter()
+        .enumerate()
+        .map(|(i, &sample)| {
+            let t = i as f64 / sample_rate as f64;
+            let pan = (2.0 * PI * rate * t).sin();
+            let (right, left) = ((pan + 1.0) * PI / 4.0).sin_cos();
+            [sample * left, sample * right]
+        })
+        .collect()
+}
+
+/// Haas (precedence effect) widening: the right channel is the left delayed by `delay_ms`
+///
+/// Delays of 5-30 ms read as width rather than echo, and summing to mono
+/// only adds a comb filter instead of cancelling.
+pub fn apply_haas(samples: &[f64], sample_rate: u32, delay_ms: f64) -> Vec<[f64; 2]> {
+    let delay = (delay_ms / 1000.0 * sample_rate as f64).round() as usize;
+    samples
+        .iter()
+        .enumerate()
+        .map(|(i, &sample)| {
+            let delayed = i.checked_sub(delay).map_or(0.0, |j| samples[j]);
+            [sample, delayed]
+        })
+        .collect()
+}
+
+/// Upmix mono to stereo with an all-pass decorrelated side signal
+///
+/// Two all-pass chains keep the spectrum flat while holding their outputs
+/// 90° apart from roughly 20 Hz to 20 kHz (at 44.1 kHz). With `a` and `b`
+/// the chain outputs, L = a·cos θ + b·sin θ and R = a·cos θ − b·sin θ for
+/// θ = width·π/4. Quadrature keeps both channels at the input's power, the
+/// correlation between them falls to cos 2θ, and L + R = 2·cos θ·a is the
+/// input with only its phase changed, so a mono fold-down has no comb
+/// filtering. Width 0 gives identical channels. Below the network's band
+/// the outputs drift back into phase, so sub-bass such as brown noise's
+/// rumble leans toward the left.
+pub fn apply_upmix(samples: &[f64], width: f64) -> Vec<[f64; 2]> {
+    // Olli Niemitalo's 90° phase-difference network; the first path is
+    // followed by a one-sample delay
+    const PATH_A: [f64; 4] = [0.6923878, 0.9360654322959, 0.9882295226860, 0.9987488452737];
+    const PATH_B: [f64; 4] = [
+        0.4021921162426,
+        0.8561710882420,
+        0.9722909545651,
+        0.9952884791278,
+    ];
+
+    let theta = width.clamp(0.0, 1.0) * PI / 4.0;
+    let (side_gain, mid_gain) = theta.sin_cos();
+    let mut path_a = AllpassChain::new(PATH_A);
+    let mut path_b = AllpassChain::new(PATH_B);
+    let mut delayed_a = 0.0;
+
+    samples
+        .iter()
+        .map(|&sample| {
+            let a = delayed_a;
+            delayed_a = path_a.process(sample);
+            let b = path_b.process(sample);
+            let (mid, side) = (a * mid_gain, b * side_gain);
+            [mid + side, mid - side]
+        })
+        .collect()
+}
+
+/// Cascade of second-order all-pass sections `H(z) = (c² - z⁻²) / (1 - c²z⁻²)`
+struct AllpassChain {
+    coefficients: [f64; 4],
+    /// Per section: `[x[n-1], x[n-2], y[n-1], y[n-2]]`
+    state: [[f64; 4]; 4],
+}
+
+impl AllpassChain {
+    fn new(coefficients: [f64; 4]) -> Self {
+        Self {
+            coefficients: coefficients.map(|c| c * c),
+            state: [[0.0; 4]; 4],
+        }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let mut x = input;
+        for (c, [x1, x2, y1, y2]) in self.coefficients.iter().zip(self.state.iter_mut()) {
+            let y = c * (x + *y2) - *x2;
+            (*x2, *x1, *y2, *y1) = (*x1, x, *y1, y);
+            x = y;
+        }
+        x
+    }
+}
+
+/// Stereo ping-pong delay: echoes alternate left, right, left, ...
+///
+/// The input is summed to mono and fed into the left delay line; each echo
+/// crosses to the other channel scaled by `feedback`, which is capped below
+/// 1.0 so the tail always decays.
+pub fn apply_pingpong_delay(
+    frames: &[[f64; 2]],
+    sample_rate: u32,
+    delay_secs: f64,
+    feedback: f64,
+    wet: f64,
+) -> Vec<[f64; 2]> {
+    let delay = ((delay_secs * sample_rate as f64) as usize).max(1);
+    let feedback = feedback.clamp(0.0, 0.95);
+    let wet = wet.clamp(0.0, 1.0);
+
+    let mut left_line = vec![0.0; delay];
+    let mut right_line = vec![0.0; delay];
+    let mut pos = 0;
+
+    frames
+        .iter()
+        .map(|&[left, right]| {
+            let echo_left = left_line[pos];
+            let echo_right = right_line[pos];
+            left_line[pos] = 0.5 * (left + right) + echo_right * feedback;
+            right_line[pos] = echo_left * feedback;
+            pos = (pos + 1) % delay;
+
+            [
+                left * (1.0 - wet) + echo_left * wet,
+                right * (1.0 - wet) + echo_right * wet,
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+
+    fn peak(samples: &[f64]) -> f64 {
+        samples.iter().fold(0.0f64, |acc, s| acc.max(s.abs()))
+    }
+
+    fn sine(len: usize) -> Vec<f64> {
+        (0..len).map(|i| 0.3 * (i as f64 * 0.05).sin()).collect()
+    }
+
+    #[test]
+    fn chain_applies_effects_in_order() {
+        let rate = 44100;
+        let mut limited_last = sine(4410);
+        let chain = parse_chain("normalize:1.0,limiter:0.5", rate).unwrap();
+        apply_chain(&mut limited_last, &chain, rate);
+        assert!(peak(&limited_last) <= 0.5 + 1e-9);
+
+        let mut normalized_last = sine(4410);
+        let chain = parse_chain("limiter:0.5,normalize:1.0", rate).unwrap();
+        apply_chain(&mut normalized_last, &chain, rate);
+        assert!((peak(&normalized_last) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn chain_parses_defaults_and_rejects_unknown_effects() {
+        let chain = parse_chain("lowpass:2000, reverb:0.3,normalize", 44100).unwrap();
+        assert!(matches!(
+            chain.as_slice(),
+            [Effect::Lowpass(_), Effect::Reverb(_), Effect::Normalize(_)]
+        ));
+        assert!(parse_chain("flanger:0.5", 44100).is_err());
+    }
+
+    #[test]
+    fn chain_rejects_non_finite_and_out_of_range_values() {
+        for spec in [
+            "lowpass:nan",
+            "tremolo:inf",
+            "bitcrush:-3",
+            "bitcrush:4.5",
+            "lowpass:30000",
+            "highpass:0",
+            "reverb:1.5",
+            "normalize:-0.1",
+            "limiter:2",
+            "saturate:0",
+        ] {
+            let err = parse_chain(spec, 44100).unwrap_err();
+            assert!(
+                err.starts_with("invalid value for effect"),
+                "{}: {}",
+                spec,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn pingpong_echoes_alternate_channels() {
+        let mut frames = vec![[0.0; 2]; 45];
+        frames[0] = [1.0, 1.0];
+        let out = apply_pingpong_delay(&frames, 1000, 0.01, 0.5, 1.0);
+
+        assert_eq!(out[10], [1.0, 0.0]);
+        assert_eq!(out[20], [0.0, 0.5]);
+        assert_eq!(out[30], [0.25, 0.0]);
+        assert_eq!(out[40], [0.0, 0.125]);
+        for (idx, frame) in out.iter().enumerate() {
+            if idx % 10 != 0 || idx == 0 {
+                assert_eq!(*frame, [0.0, 0.0], "frame {}", idx);
+            }
+        }
+    }
+
+    #[test]
+    fn four_bit_crush_leaves_sixteen_levels() {
+        let mut samples: Vec<f64> = (0..10_000).map(|i| (i as f64 * 0.01).sin()).collect();
+        apply_bitcrush(&mut samples, 4, 1);
+
+        let mut levels: Vec<i64> = samples.iter().map(|s| (s * 8.0) as i64).collect();
+        levels.sort_unstable();
+        levels.dedup();
+        assert_eq!(levels.len(), 16);
+        assert!(samples.iter().all(|s| (s * 8.0).fract() == 0.0));
+    }
+
+    #[test]
+    fn bitcrush_hold_repeats_samples() {
+        let mut samples = sine(12);
+        apply_bitcrush(&mut samples, 16, 4);
+        for block in samples.chunks(4) {
+            assert!(block.iter().all(|&s| s == block[0]));
+        }
+    }
+
+    #[test]
+    fn filter_sweep_opens_and_closes_at_the_lfo_rate() {
+        let rate = 44100;
+        let mut samples: Vec<f64> = (0..4 * rate)
+            .map(|i| 0.5 * (2.0 * PI * 3000.0 * i as f64 / rate as f64).sin())
+            .collect();
+        apply_filter_sweep(&mut samples, rate as u32, 200.0, 8000.0, 0.5);
+
+        // Cutoff peaks at 1 s and 3 s and bottoms out at 2 s
+        let level = |secs: f64| {
+            let center = (secs * rate as f64) as usize;
+            crate::analysis::rms(&samples[center - 2205..center + 2205])
+        };
+        assert!(
+            level(1.0) > 10.0 * level(2.0),
+            "{} vs {}",
+            level(1.0),
+            level(2.0)
+        );
+        assert!(
+            level(3.0) > 10.0 * level(2.0),
+            "{} vs {}",
+            level(3.0),
+            level(2.0)
+        );
+    }
+
+    #[test]
+    fn envelope_presets_match_their_docs_and_stay_in_range() {
+        let adsr = |a, d, s, r| Adsr {
+            attack: a,
+            decay: d,
+            sustain: s,
+            release: r,
+        };
+        assert_eq!(EnvelopePreset::Pad.adsr(), adsr(2.0, 1.0, 0.8, 3.0));
+        assert_eq!(EnvelopePreset::Pluck.adsr(), adsr(0.005, 0.4, 0.0, 0.1));
+        assert_eq!(EnvelopePreset::Swell.adsr(), adsr(8.0, 0.0, 1.0, 4.0));
+        assert_eq!(EnvelopePreset::Gate.adsr(), adsr(0.005, 0.0, 1.0, 0.005));
+
+        let rate = 1000;
+        let tone: Vec<f64> = (0..20 * rate)
+            .map(|i| 0.8 * (i as f64 * 0.3).sin())
+            .collect();
+        for preset in EnvelopePreset::value_variants() {
+            let mut shaped = tone.clone();
+            preset.adsr().apply(&mut shaped, rate as u32);
+            assert!(peak(&shaped) <= peak(&tone), "{:?}", preset);
+            assert!(shaped.iter().all(|s| s.is_finite()));
+        }
+    }
+
+    #[test]
+    fn rotation_level_difference_follows_the_rate() {
+        let rate = 8000;
+        let tone: Vec<f64> = (0..4 * rate)
+            .map(|i| 0.5 * (2.0 * PI * 500.0 * i as f64 / rate as f64).sin())
+            .collect();
+        let frames = apply_rotation(&tone, rate as u32, 0.5);
+
+        // Right minus left RMS over 50 ms around `secs`
+        let difference = |secs: f64| {
+            let center = (secs * rate as f64) as usize;
+            let window = &frames[center - 200..center + 200];
+            let left: Vec<f64> = window.iter().map(|frame| frame[0]).collect();
+            let right: Vec<f64> = window.iter().map(|frame| frame[1]).collect();
+            crate::analysis::rms(&right) - crate::analysis::rms(&left)
+        };
+
+        // One rotation every 2 s: hard right at 0.5 s, center at 1 s, hard left at 1.5 s
+        for cycle in [0.0, 2.0] {
+            assert!(difference(cycle + 0.5) > 0.3);
+            assert!(difference(cycle + 1.0).abs() < 0.05);
+            assert!(difference(cycle + 1.5) < -0.3);
+        }
+
+        let power: Vec<f64> = frames.iter().map(|[l, r]| l * l + r * r).collect();
+        let expected: Vec<f64> = tone.iter().map(|s| s * s).collect();
+        for (p, e) in power.iter().zip(&expected) {
+            assert!((p - e).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn haas_right_channel_is_left_delayed_by_the_requested_samples() {
+        let samples = sine(2000);
+        // 10 ms at 8 kHz
+        let frames = apply_haas(&samples, 8000, 10.0);
+        let delay = 80;
+
+        assert_eq!(frames.len(), samples.len());
+        for (i, frame) in frames.iter().enumerate() {
+            assert_eq!(frame[0], samples[i]);
+            let expected = if i < delay { 0.0 } else { samples[i - delay] };
+            assert_eq!(frame[1], expected);
+        }
+    }
+
+    #[test]
+    fn saturation_adds_second_and_third_harmonics() {
+        // 8192 samples at 8192 Hz puts each whole hertz on its own bin
+        let rate = 8192;
+        let tone: Vec<f64> = (0..rate)
+            .map(|i| 0.5 * (2.0 * PI * 500.0 * i as f64 / rate as f64).sin())
+            .collect();
+        let harmonics = |samples: &[f64]| {
+            let spectrum = crate::analysis::magnitude_spectrum(samples);
+            [
+                spectrum[1000] / spectrum[500],
+                spectrum[1500] / spectrum[500],
+            ]
+        };
+
+        let clean = harmonics(&tone);
+        let mut warm = tone.clone();
+        apply_saturation(&mut warm, 2.0);
+        let saturated = harmonics(&warm);
+
+        for (before, after) in clean.iter().zip(saturated) {
+            assert!(*before < 1e-6);
+            assert!(after > 0.01);
+        }
+    }
+
+    #[test]
+    fn upmix_decorrelates_channels_and_keeps_mono_energy() {
+        let rate = 44100;
+        let tone: Vec<f64> = (0..rate)
+            .map(|i| 0.5 * (2.0 * PI * 1000.0 * i as f64 / rate as f64).sin())
+            .collect();
+        // Skip the all-pass chains' start-up transient
+        let frames = &apply_upmix(&tone, 1.0)[4096..];
+        let input_rms = crate::analysis::rms(&tone[4096..]);
+
+        let left: Vec<f64> = frames.iter().map(|frame| frame[0]).collect();
+        let right: Vec<f64> = frames.iter().map(|frame| frame[1]).collect();
+        let correlation = left.iter().zip(&right).map(|(l, r)| l * r).sum::<f64>()
+            / (left.iter().map(|l| l * l).sum::<f64>() * right.iter().map(|r| r * r).sum::<f64>())
+                .sqrt();
+        assert!(correlation.abs() < 0.1);
+
+        // At full width L + R = 2·cos(π/4)·a, where `a` is the input phase-shifted
+        let sum: Vec<f64> = frames
+            .iter()
+            .map(|frame| (frame[0] + frame[1]) / (2.0 * (PI / 4.0).cos()))
+            .collect();
+        for channel in [&left, &right, &sum] {
+            let ratio = crate::analysis::rms(channel) / input_rms;
+            assert!((ratio - 1.0).abs() < 0.05);
+        }
+    }
+}
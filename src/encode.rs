@@ -0,0 +1,76 @@
+//! Compressed copies of rendered WAV files.
+//!
+//! WAV stays the primary output. Extra formats are produced by handing the
+//! finished WAV to an external `ffmpeg`, which keeps encoder libraries out of
+//! the build.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Additional format to encode each generated file to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// MP3 (LAME, VBR quality 2)
+    Mp3,
+    /// Opus in an Ogg container, 128 kbps
+    Opus,
+    /// Lossless FLAC
+    Flac,
+    /// Ogg Vorbis, quality 6
+    Ogg,
+}
+
+impl OutputFormat {
+    /// File extension, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Mp3 => "mp3",
+            OutputFormat::Opus => "opus",
+            OutputFormat::Flac => "flac",
+            OutputFormat::Ogg => "ogg",
+        }
+    }
+
+    /// ffmpeg codec arguments for this format
+    fn codec_args(self) -> &'static [&'static str] {
+        match self {
+            OutputFormat::Mp3 => &["-c:a", "libmp3lame", "-q:a", "2"],
+            OutputFormat::Opus => &["-c:a", "libopus", "-b:a", "128k"],
+            OutputFormat::Flac => &["-c:a", "flac"],
+            OutputFormat::Ogg => &["-c:a", "libvorbis", "-q:a", "6"],
+        }
+    }
+}
+
+/// Whether an `ffmpeg` executable can be run
+pub fn ffmpeg_available() -> bool {
+    Command::new("ffmpeg")
+        .arg("-version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Encode a WAV file to `format` alongside it, returning the new file's path
+pub fn encode(wav_path: &Path, format: OutputFormat) -> io::Result<PathBuf> {
+    let out_path = wav_path.with_extension(format.extension());
+    let output = Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-i"])
+        .arg(wav_path)
+        .args(format.codec_args())
+        .arg(&out_path)
+        .stdin(Stdio::null())
+        .output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "ffmpeg failed to encode {} as {}: {}",
+            wav_path.display(),
+            format.extension(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(out_path)
+}
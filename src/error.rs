@@ -0,0 +1,97 @@
+//! Errors returned by the generators.
+
+use std::fmt;
+use std::io;
+
+/// Anything that can stop a file from being generated
+#[derive(Debug)]
+pub enum SpiritError {
+    /// Filesystem or stream failure outside the WAV codec
+    Io(io::Error),
+    /// WAV encoding or decoding failure
+    Hound(hound::Error),
+    /// A frequency that is not positive, not finite, or above Nyquist
+    InvalidFrequency(String),
+    /// An input file (or existing output) in a format that cannot be used
+    UnsupportedFormat(String),
+    /// Any other parameter outside its accepted range
+    InvalidInput(String),
+    /// Generation stopped by Ctrl-C before the file was written
+    Cancelled,
+}
+
+impl fmt::Display for SpiritError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpiritError::Io(err) => write!(f, "I/O error: {}", err),
+            SpiritError::Hound(err) => write!(f, "WAV error: {}", err),
+            SpiritError::InvalidFrequency(message) => write!(f, "invalid frequency: {}", message),
+            SpiritError::UnsupportedFormat(message) => write!(f, "unsupported format: {}", message),
+            SpiritError::InvalidInput(message) => write!(f, "{}", message),
+            SpiritError::Cancelled => write!(f, "generation cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for SpiritError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SpiritError::Io(err) => Some(err),
+            SpiritError::Hound(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SpiritError {
+    fn from(err: io::Error) -> Self {
+        SpiritError::Io(err)
+    }
+}
+
+impl From<hound::Error> for SpiritError {
+    fn from(err: hound::Error) -> Self {
+        SpiritError::Hound(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{GenerationMode, DEFAULT_CARRIER_HZ};
+    use crate::test_support;
+    use std::error::Error;
+
+    #[test]
+    fn invalid_frequency_yields_its_own_variant() {
+        let gen = test_support::generator("invalid-frequency", 0.1);
+        for frequency in [f64::NAN, f64::INFINITY] {
+            let result = gen.generate_custom(frequency, &GenerationMode::Sine, DEFAULT_CARRIER_HZ);
+            assert!(
+                matches!(result, Err(SpiritError::InvalidFrequency(_))),
+                "{} Hz gave {:?}",
+                frequency,
+                result
+            );
+        }
+
+        // Above Nyquist at 44.1 kHz
+        let result = gen.generate_comb_file(100.0, 30_000.0, 4);
+        assert!(matches!(result, Err(SpiritError::InvalidFrequency(_))));
+        assert!(test_support::wav_files(&gen.output_dir).is_empty());
+    }
+
+    #[test]
+    fn io_and_hound_errors_convert_and_keep_their_source() {
+        let io: SpiritError = io::Error::new(io::ErrorKind::NotFound, "missing").into();
+        assert!(matches!(io, SpiritError::Io(_)));
+        assert!(io.source().is_some());
+        assert_eq!(io.to_string(), "I/O error: missing");
+
+        let hound: SpiritError = hound::Error::Unsupported.into();
+        assert!(matches!(hound, SpiritError::Hound(_)));
+        assert!(hound.source().is_some());
+
+        assert!(SpiritError::Cancelled.source().is_none());
+    }
+}
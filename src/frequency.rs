@@ -5,3 +5,247 @@
 
 // Include the generated frequency module
 include!(concat!(env!("OUT_DIR"), "/frequency.rs"));
+
+/// Find the entry a default-template filename (`{prefix}_{name}_{hz}hz.wav`) came from
+///
+/// Any leading directories and the `.wav` extension are optional. Returns
+/// `None` unless a category prefix, entry name and frequency all match.
+pub fn parse_frequency_filename(filename: &str) -> Option<(Category, &'static FrequencyInfo)> {
+    let stem = std::path::Path::new(filename).file_name()?.to_str()?;
+    let stem = stem.strip_suffix(".wav").unwrap_or(stem);
+    let (rest, hz) = stem.strip_suffix("hz")?.rsplit_once('_')?;
+    let hz: f64 = hz.parse().ok()?;
+
+    Category::all().iter().find_map(|&category| {
+        let name = rest
+            .strip_prefix(category.file_prefix())?
+            .strip_prefix('_')?;
+        category
+            .frequencies()
+            .iter()
+            .find(|f| f.name == name && (f.hz - hz).abs() < 0.005)
+            .map(|f| (category, f))
+    })
+}
+
+/// Transpose a frequency by a whole number of octaves
+pub fn octave_transpose(hz: f64, octaves: i32) -> f64 {
+    hz * 2f64.powi(octaves)
+}
+
+/// A root and its octaves (`octaves` frequencies in total), stopping below `limit_hz`
+pub fn octave_stack(root: f64, octaves: u32, limit_hz: f64) -> Vec<f64> {
+    (0..octaves as i32)
+        .map(|octave| octave_transpose(root, octave))
+        .take_while(|&hz| hz < limit_hz)
+        .collect()
+}
+
+/// Frequencies at each ratio above a root (e.g. 220 x 1.5 = 330, a just fifth)
+pub fn interval_frequencies(root: f64, ratios: &[f64]) -> Vec<f64> {
+    ratios.iter().map(|ratio| root * ratio).collect()
+}
+
+/// Overshoot of twelve pure fifths past seven octaves: (3/2)^12 / 2^7 = 3^12 / 2^19
+pub const PYTHAGOREAN_COMMA: f64 = 531441.0 / 524288.0;
+
+/// Repeated digit sum of the integer part of a frequency (e.g. 528 -> 15 -> 6)
+pub fn digit_root(hz: f64) -> u64 {
+    let n = hz.trunc() as u64;
+    if n == 0 {
+        0
+    } else {
+        1 + (n - 1) % 9
+    }
+}
+
+/// Standard concert pitch for A4
+pub const DEFAULT_A4_HZ: f64 = 440.0;
+
+const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+/// Reference tuning that equal-tempered notes are derived from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TuningSystem {
+    /// Concert pitch, A4 = 440 Hz
+    Et440,
+    /// A4 = 432 Hz
+    Et432,
+    /// Scientific (Sauveur) pitch, C4 = 256 Hz
+    Scientific,
+}
+
+impl TuningSystem {
+    /// The `(MIDI note, Hz)` pair every other note is derived from
+    fn reference(self) -> (i32, f64) {
+        match self {
+            TuningSystem::Et440 => (69, 440.0),
+            TuningSystem::Et432 => (69, 432.0),
+            TuningSystem::Scientific => (60, 256.0),
+        }
+    }
+
+    /// Frequency of a MIDI note number (the reference note is exact)
+    pub fn note_hz(self, midi: i32) -> f64 {
+        let (reference_note, reference_hz) = self.reference();
+        reference_hz * 2f64.powf((midi - reference_note) as f64 / 12.0)
+    }
+
+    /// Frequency of A4 under this system
+    pub fn a4_hz(self) -> f64 {
+        self.note_hz(69)
+    }
+}
+
+/// Parse a note name such as `A4`, `C#3` or `Db5` into a MIDI note number
+pub fn parse_note(name: &str) -> Option<i32> {
+    let mut chars = name.chars();
+    let letter = chars.next()?.to_ascii_uppercase();
+    let base = NOTE_NAMES
+        .iter()
+        .position(|n| n.len() == 1 && n.starts_with(letter))? as i32;
+
+    let rest = chars.as_str();
+    let (offset, octave) = match rest.chars().next()? {
+        '#' => (1, &rest[1..]),
+        'b' => (-1, &rest[1..]),
+        _ => (0, rest),
+    };
+    let octave: i32 = octave.parse().ok()?;
+    Some((octave + 1) * 12 + base + offset)
+}
+
+/// Nearest equal-tempered note and its deviation in cents (e.g. 432 -> A4, -31.8)
+pub fn hz_to_note(hz: f64, a4_ref: f64) -> (String, f64) {
+    let midi = 69.0 + 12.0 * (hz / a4_ref).log2();
+    let nearest = midi.round();
+    let cents = (midi - nearest) * 100.0;
+
+    let nearest = nearest as i32;
+    let name = NOTE_NAMES[nearest.rem_euclid(12) as usize];
+    let octave = nearest.div_euclid(12) - 1;
+    (format!("{}{}", name, octave), cents)
+}
+
+/// Frequency of the equal-tempered note nearest to `hz`
+pub fn nearest_note_hz(hz: f64, a4_ref: f64) -> f64 {
+    let midi = (69.0 + 12.0 * (hz / a4_ref).log2()).round();
+    a4_ref * 2f64.powf((midi - 69.0) / 12.0)
+}
+
+/// Highest frequency considered plausible for a database entry
+pub const MAX_SANE_HZ: f64 = 25_000.0;
+
+/// Describe any invalid entries in a list of frequencies
+///
+/// Zero-Hz entries are allowed as placeholders (e.g. The Fool in tarot).
+pub fn frequency_issues(frequencies: &[FrequencyInfo]) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    for (idx, f) in frequencies.iter().enumerate() {
+        if f.hz.is_nan() {
+            issues.push(format!("{}: frequency is NaN", f.name));
+        } else if f.hz < 0.0 {
+            issues.push(format!("{}: negative frequency {} Hz", f.name, f.hz));
+        } else if f.hz > MAX_SANE_HZ {
+            issues.push(format!(
+                "{}: {} Hz exceeds the {} Hz limit",
+                f.name, f.hz, MAX_SANE_HZ
+            ));
+        }
+
+        if frequencies[..idx].iter().any(|other| other.name == f.name) {
+            issues.push(format!("{}: duplicate name", f.name));
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(hz: f64, name: &'static str) -> FrequencyInfo {
+        FrequencyInfo {
+            hz,
+            name,
+            description: "test fixture",
+        }
+    }
+
+    #[test]
+    fn frequency_issues_flags_bad_entries() {
+        let frequencies = [
+            fixture(528.0, "ok"),
+            fixture(0.0, "placeholder"),
+            fixture(-1.0, "negative"),
+            fixture(f64::NAN, "nan"),
+            fixture(MAX_SANE_HZ * 2.0, "too_high"),
+            fixture(639.0, "ok"),
+        ];
+
+        let issues = frequency_issues(&frequencies);
+        assert_eq!(issues.len(), 4, "{:?}", issues);
+        for name in ["negative", "nan", "too_high", "ok: duplicate"] {
+            assert!(
+                issues.iter().any(|issue| issue.starts_with(name)),
+                "{}",
+                name
+            );
+        }
+        assert!(!issues.iter().any(|issue| issue.starts_with("placeholder")));
+    }
+
+    #[test]
+    fn bundled_frequencies_have_no_issues() {
+        for category in Category::all() {
+            assert!(frequency_issues(category.frequencies()).is_empty());
+        }
+    }
+
+    #[test]
+    fn hz_to_note_reports_cents_from_nearest_note() {
+        let (note, cents) = hz_to_note(440.0, 440.0);
+        assert_eq!(note, "A4");
+        assert!(cents.abs() < 1e-9);
+
+        let (note, cents) = hz_to_note(432.0, 440.0);
+        assert_eq!(note, "A4");
+        assert!((cents + 31.77).abs() < 0.01, "{} cents", cents);
+
+        assert_eq!(hz_to_note(432.0, 432.0).0, "A4");
+        assert_eq!(hz_to_note(261.63, 440.0).0, "C4");
+    }
+
+    #[test]
+    fn interval_ratios_multiply_the_root() {
+        assert_eq!(
+            interval_frequencies(220.0, &[1.0, 1.5, 1.25, 2.0]),
+            [220.0, 330.0, 275.0, 440.0]
+        );
+    }
+
+    #[test]
+    fn octave_stack_doubles_up_to_the_limit() {
+        assert_eq!(octave_stack(55.0, 4, 22050.0), [55.0, 110.0, 220.0, 440.0]);
+        assert_eq!(octave_stack(5000.0, 4, 22050.0), [5000.0, 10000.0, 20000.0]);
+    }
+
+    #[test]
+    fn scientific_pitch_puts_c4_at_256_hz() {
+        let c4 = parse_note("C4").unwrap();
+        assert_eq!(TuningSystem::Scientific.note_hz(c4), 256.0);
+        assert_eq!(TuningSystem::Scientific.note_hz(c4 + 12), 512.0);
+        assert!((TuningSystem::Scientific.a4_hz() - 430.54).abs() < 0.01);
+        assert_eq!(TuningSystem::Et432.a4_hz(), 432.0);
+    }
+
+    #[test]
+    fn pythagorean_comma_matches_twelve_fifths_over_seven_octaves() {
+        assert!((PYTHAGOREAN_COMMA - 1.013643).abs() < 1e-6);
+        assert!((1.5f64.powi(12) / 2f64.powi(7) - PYTHAGOREAN_COMMA).abs() < 1e-12);
+    }
+}
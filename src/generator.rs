@@ -3,20 +3,153 @@
 //! This module contains the AudioGenerator struct with all waveform generation
 //! methods and WAV file output functionality.
 
-use std::f64::consts::PI;
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
 use std::fs;
-use std::path::PathBuf;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
-use hound::{SampleFormat, WavSpec, WavWriter};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
 
+use crate::analysis;
 use crate::config::{AudioConfig, AMPLITUDE};
-use crate::frequency::{BrainwaveState, Category, FrequencyInfo, BRAINWAVE_STATES};
+use crate::effects::{self, Adsr, Effect};
+use crate::encode::{self, OutputFormat};
+use crate::error::SpiritError;
+use crate::frequency::{
+    digit_root, hz_to_note, interval_frequencies, nearest_note_hz, octave_stack, octave_transpose,
+    parse_note, BrainwaveState, Category, FrequencyInfo, TuningSystem, BRAINWAVE_STATES,
+    DEFAULT_A4_HZ, PYTHAGOREAN_COMMA,
+};
+use crate::oscillator::{Glide, PhaseState, SineStream, Waveform};
+use crate::resample::{self, Resampler};
+use crate::rng::{RngKind, DEFAULT_SEED};
+use crate::wav::{self, ChannelLayout, Dither};
+
+/// Sine length beyond which phase accumulation is used automatically (1 hour)
+const PHASE_ACCUMULATOR_SECS: f64 = 3600.0;
+
+/// Length of one counting beep; each is followed by a gap as long
+const BEEP_SECS: f64 = 0.08;
+/// Length of the single lower beep that counts zero
+const ZERO_BEEP_SECS: f64 = 0.3;
+/// Silence after each digit of a beep prefix
+const BEEP_PAUSE_SECS: f64 = 0.4;
+
+/// Carrier for binaural and isochronic custom tones when none is given
+pub const DEFAULT_CARRIER_HZ: f64 = 200.0;
+
+/// Fundamental of the Om tone in Hz
+pub const OM_HZ: f64 = 136.1;
+
+/// Smallest carrier-to-beat ratio at which a beat or pulse stays distinct from its carrier
+const MIN_CARRIER_RATIO: f64 = 10.0;
 
 /// Audio generator that holds configuration and provides all generation methods
 pub struct AudioGenerator {
     pub config: AudioConfig,
     pub output_dir: PathBuf,
     pub duration: f64,
+    /// Extend existing output files instead of overwriting them
+    pub append: bool,
+    /// Leave existing complete files in place instead of regenerating them
+    pub resume: bool,
+    /// Refuse to write files larger than this many bytes
+    pub max_file_bytes: Option<u64>,
+    /// Curve used for fade-in/out ramps
+    pub fade_window: FadeWindow,
+    /// Flat center fraction of the Tukey window (0 = Hann, 1 = no fade)
+    pub tukey_flat: f64,
+    /// Disable all fade-in/out ramps
+    pub no_fade: bool,
+    /// Effects applied to every channel before writing
+    pub effects: Vec<Effect>,
+    /// Per-sample gain curve from `--envelope-from`, at the output rate
+    pub amplitude_envelope: Option<Vec<f64>>,
+    /// Stereo balance from -1.0 (left only) to 1.0 (right only)
+    pub balance: f64,
+    /// Seed for noise and random phase generation
+    pub seed: u64,
+    /// PRNG algorithm seeded with `seed`
+    pub rng: RngKind,
+    /// Give each layered frequency a random initial phase
+    pub random_phase: bool,
+    /// Integrated loudness target in LUFS applied before writing
+    pub target_lufs: Option<f64>,
+    /// Noise color mixed under every output
+    pub mix_noise: Option<NoiseColor>,
+    /// Level of the mixed-in noise (0 = none)
+    pub noise_level: f64,
+    /// Boost the tone to track the mixed-in noise's envelope
+    pub tone_auto_gain: bool,
+    /// Reference pitch for A4 used by the `{note}` filename placeholder
+    pub tuning: f64,
+    /// Tuning system for named notes (overrides `tuning` when set)
+    pub tuning_system: Option<TuningSystem>,
+    /// Filename template for database frequencies (without extension)
+    pub name_template: String,
+    /// Set (e.g. by a Ctrl-C handler) to stop before the next file is written
+    pub cancel: Arc<AtomicBool>,
+    /// Render a category as one sequential file instead of one file per tone
+    pub concat: bool,
+    /// With `concat`, also write each category tone as its own file
+    pub both: bool,
+    /// Match segment RMS levels when concatenating
+    pub match_loudness: bool,
+    /// Seconds of silence between categories when `all` is concatenated into one file
+    pub category_gap: Option<f64>,
+    /// Write a `.cue` sheet next to concatenated files
+    pub cue_sheet: bool,
+    /// Always generate sines with a phase accumulator
+    pub phase_accumulator: bool,
+    /// Round sample counts to the nearest sample instead of truncating
+    pub exact_duration: bool,
+    /// Target peak-to-RMS ratio in dB for layered and comb files
+    pub crest_factor: Option<f64>,
+    /// Print an ASCII spectrum of each file after writing
+    pub preview_spectrum: bool,
+    /// Fraction of each isochronic pulse spent above half amplitude
+    pub duty: f64,
+    /// Dither used when writing 16-bit integer files (None = plain rounding)
+    pub dither: Option<Dither>,
+    /// ADSR envelope shaping custom and note output
+    pub envelope: Option<Adsr>,
+    /// Formats each WAV is additionally encoded to
+    pub also_formats: Vec<OutputFormat>,
+    /// Prefix database tones with beeps counting their hundreds and tens digits
+    pub beep_prefix: bool,
+    /// Write stereo output as separate Mid and Side mono files
+    pub ms_split: bool,
+    /// Side gain applied to stereo output (0 = mono, 1 = unchanged)
+    pub width: f64,
+    /// Exchange left and right when writing stereo output
+    pub swap_channels: bool,
+    /// Downmix stereo output to mono before writing
+    pub force_mono: bool,
+    /// Auto-pan rate in Hz for mono output (None = stay mono)
+    pub rotate: Option<f64>,
+    /// Haas widening delay for mono output in milliseconds (None = stay mono)
+    pub haas: Option<f64>,
+    /// Decorrelated upmix width for mono output, 0-1 (None = stay mono)
+    pub upmix: Option<f64>,
+    /// Oversample the effects chain 2x while rendering the Rife category
+    pub antialias: bool,
+    /// Ping-pong delay time in seconds (None = off)
+    pub delay: Option<f64>,
+    /// Ping-pong delay feedback (0-0.95)
+    pub delay_feedback: f64,
+    /// Ping-pong delay wet mix (0-1)
+    pub delay_mix: f64,
+    /// Number of files written so far
+    saved_files: AtomicUsize,
+    /// Non-finite samples replaced with silence across every file written
+    replaced_samples: AtomicUsize,
+    /// Every distinct path written, in order
+    written: Mutex<Vec<PathBuf>>,
+    /// Database entries skipped for being at or above Nyquist, as `(label, Hz)`
+    skipped: Mutex<Vec<(String, f64)>>,
 }
 
 impl AudioGenerator {
@@ -25,12 +158,71 @@ impl AudioGenerator {
             config,
             output_dir,
             duration,
+            append: false,
+            resume: false,
+            max_file_bytes: None,
+            fade_window: FadeWindow::Linear,
+            tukey_flat: 0.5,
+            no_fade: false,
+            effects: Vec::new(),
+            amplitude_envelope: None,
+            balance: 0.0,
+            seed: DEFAULT_SEED,
+            rng: RngKind::default(),
+            random_phase: false,
+            target_lufs: None,
+            mix_noise: None,
+            noise_level: 0.2,
+            tone_auto_gain: false,
+            tuning: DEFAULT_A4_HZ,
+            tuning_system: None,
+            name_template: "{prefix}_{name}_{hz}hz".to_string(),
+            cancel: Arc::new(AtomicBool::new(false)),
+            concat: false,
+            both: false,
+            match_loudness: false,
+            envelope: None,
+            beep_prefix: false,
+            also_formats: Vec::new(),
+            dither: None,
+            category_gap: None,
+            cue_sheet: false,
+            phase_accumulator: false,
+            exact_duration: false,
+            crest_factor: None,
+            preview_spectrum: false,
+            duty: 0.5,
+            ms_split: false,
+            width: 1.0,
+            swap_channels: false,
+            force_mono: false,
+            rotate: None,
+            haas: None,
+            upmix: None,
+            antialias: false,
+            delay: None,
+            delay_feedback: 0.4,
+            delay_mix: 0.3,
+            saved_files: AtomicUsize::new(0),
+            replaced_samples: AtomicUsize::new(0),
+            written: Mutex::new(Vec::new()),
+            skipped: Mutex::new(Vec::new()),
         }
     }
 
     /// Generate a pure sine wave at the given frequency
+    ///
+    /// Files longer than `PHASE_ACCUMULATOR_SECS` (or any file with
+    /// `phase_accumulator` set) accumulate a wrapped phase instead of evaluating
+    /// `sin(2πft)`, so precision does not degrade as `t` grows.
     pub fn generate_sine_wave(&self, frequency: f64, duration_secs: f64) -> Vec<f64> {
-        let num_samples = (self.config.sample_rate as f64 * duration_secs) as usize;
+        let num_samples = self.sample_count(duration_secs);
+        if self.phase_accumulator || duration_secs > PHASE_ACCUMULATOR_SECS {
+            return SineStream::new(frequency, self.config.sample_rate)
+                .take(num_samples)
+                .collect();
+        }
+
         (0..num_samples)
             .map(|i| {
                 let t = i as f64 / self.config.sample_rate as f64;
@@ -46,7 +238,7 @@ impl AudioGenerator {
         beat_freq: f64,
         duration_secs: f64,
     ) -> Vec<[f64; 2]> {
-        let num_samples = (self.config.sample_rate as f64 * duration_secs) as usize;
+        let num_samples = self.sample_count(duration_secs);
         let right_freq = base_freq + beat_freq;
 
         (0..num_samples)
@@ -59,6 +251,89 @@ impl AudioGenerator {
             .collect()
     }
 
+    /// Generate a binaural beat whose rate moves linearly from `start_beat` to `end_beat`
+    ///
+    /// The left channel holds the base frequency; the right glides from
+    /// base + start to base + end with its phase integrated, so the beat
+    /// rate at any moment is exactly the frequency difference.
+    pub fn generate_binaural_ramp(
+        &self,
+        base_freq: f64,
+        start_beat: f64,
+        end_beat: f64,
+        duration_secs: f64,
+    ) -> Vec<[f64; 2]> {
+        let num_samples = self.sample_count(duration_secs);
+        let glide = Glide {
+            start_freq: base_freq + start_beat,
+            end_freq: base_freq + end_beat,
+            total_samples: num_samples as u64,
+            sample_rate: self.config.sample_rate,
+        };
+
+        let mut right = vec![0.0; num_samples];
+        glide.render(&mut PhaseState::default(), &mut right);
+        let mut left_phase = PhaseState::default();
+        right
+            .into_iter()
+            .map(|r| {
+                let left = AMPLITUDE * left_phase.next_sine(base_freq, self.config.sample_rate);
+                [left, r]
+            })
+            .collect()
+    }
+
+    /// Generate binaural beats on several carriers at once
+    ///
+    /// Each carrier plays on the left and carrier + beat on the right; the
+    /// carriers are summed and scaled so the peak stays at AMPLITUDE.
+    pub fn generate_multi_binaural(
+        &self,
+        carriers: &[f64],
+        beat_freq: f64,
+        duration_secs: f64,
+    ) -> Vec<[f64; 2]> {
+        let num_samples = self.sample_count(duration_secs);
+        let scale = AMPLITUDE / carriers.len().max(1) as f64;
+
+        (0..num_samples)
+            .map(|i| {
+                let t = i as f64 / self.config.sample_rate as f64;
+                carriers.iter().fold([0.0, 0.0], |[left, right], &carrier| {
+                    [
+                        left + scale * (2.0 * PI * carrier * t).sin(),
+                        right + scale * (2.0 * PI * (carrier + beat_freq) * t).sin(),
+                    ]
+                })
+            })
+            .collect()
+    }
+
+    /// Layer several binaural beats on one base carrier
+    ///
+    /// Each beat contributes its own carrier pair from
+    /// [`generate_binaural_beat`](Self::generate_binaural_beat); the pairs are
+    /// summed and scaled by the beat count so the mix keeps a single beat's level.
+    pub fn generate_multi_beat(
+        &self,
+        base_freq: f64,
+        beats: &[f64],
+        duration_secs: f64,
+    ) -> Vec<[f64; 2]> {
+        let num_samples = self.sample_count(duration_secs);
+        let scale = 1.0 / beats.len().max(1) as f64;
+
+        let mut mixed = vec![[0.0, 0.0]; num_samples];
+        for &beat in beats {
+            let pair = self.generate_binaural_beat(base_freq, beat, duration_secs);
+            for (out, [left, right]) in mixed.iter_mut().zip(pair) {
+                out[0] += scale * left;
+                out[1] += scale * right;
+            }
+        }
+        mixed
+    }
+
     /// Generate an isochronic tone (amplitude-modulated carrier)
     pub fn generate_isochronic_tone(
         &self,
@@ -66,13 +341,13 @@ impl AudioGenerator {
         pulse_freq: f64,
         duration_secs: f64,
     ) -> Vec<f64> {
-        let num_samples = (self.config.sample_rate as f64 * duration_secs) as usize;
+        let num_samples = self.sample_count(duration_secs);
 
         (0..num_samples)
             .map(|i| {
                 let t = i as f64 / self.config.sample_rate as f64;
                 let carrier = (2.0 * PI * carrier_freq * t).sin();
-                let envelope = (0.5 * (1.0 + (2.0 * PI * pulse_freq * t).sin())).clamp(0.0, 1.0);
+                let envelope = isochronic_envelope((pulse_freq * t).rem_euclid(1.0), self.duty);
                 AMPLITUDE * carrier * envelope
             })
             .collect()
@@ -80,19 +355,27 @@ impl AudioGenerator {
 
     /// Generate an Om tone (136.1 Hz with harmonics)
     pub fn generate_om_tone(&self, duration_secs: f64) -> Vec<f64> {
-        let num_samples = (self.config.sample_rate as f64 * duration_secs) as usize;
-        let fade_samples = (self.config.sample_rate as f64 * 0.5) as usize;
-        let base = 136.1;
+        self.om_wave(0.0, duration_secs)
+    }
+
+    /// Om tone with every partial shifted up by `offset_hz`
+    ///
+    /// A constant offset, rather than scaling the fundamental, makes each
+    /// harmonic beat against its unshifted counterpart at the same rate.
+    fn om_wave(&self, offset_hz: f64, duration_secs: f64) -> Vec<f64> {
+        let num_samples = self.sample_count(duration_secs);
+        let fade_samples = self.fade_samples(num_samples, 0.5);
+        let base = OM_HZ;
 
         (0..num_samples)
             .map(|i| {
                 let t = i as f64 / self.config.sample_rate as f64;
 
-                let wave = (2.0 * PI * base * t).sin()
-                    + 0.5 * (2.0 * PI * base * 2.0 * t).sin()
-                    + 0.25 * (2.0 * PI * base * 3.0 * t).sin();
+                let wave = (2.0 * PI * (base + offset_hz) * t).sin()
+                    + 0.5 * (2.0 * PI * (base * 2.0 + offset_hz) * t).sin()
+                    + 0.25 * (2.0 * PI * (base * 3.0 + offset_hz) * t).sin();
 
-                let envelope = compute_fade_envelope(i, num_samples, fade_samples);
+                let envelope = self.fade_envelope(i, num_samples, fade_samples);
                 AMPLITUDE * wave * envelope / 1.75
             })
             .collect()
@@ -104,25 +387,325 @@ impl AudioGenerator {
         frequencies: &[f64],
         duration_secs: f64,
     ) -> Vec<f64> {
-        let num_samples = (self.config.sample_rate as f64 * duration_secs) as usize;
-        let scale = 1.0 / frequencies.len() as f64;
+        let partials: Vec<(f64, f64)> = frequencies.iter().map(|&freq| (freq, 1.0)).collect();
+        self.generate_layered_frequencies_weighted(&partials, duration_secs)
+    }
+
+    /// Generate layered `(frequency, weight)` partials, normalized by total weight
+    ///
+    /// With random phases enabled the partials no longer peak together, so the
+    /// sum is normalized to its actual peak instead, allowing a louder result.
+    pub fn generate_layered_frequencies_weighted(
+        &self,
+        partials: &[(f64, f64)],
+        duration_secs: f64,
+    ) -> Vec<f64> {
+        self.layer_partials(partials, duration_secs, self.random_phase)
+    }
+
+    /// Sum weighted partials, optionally starting each at a seeded random phase
+    fn layer_partials(
+        &self,
+        partials: &[(f64, f64)],
+        duration_secs: f64,
+        random_phase: bool,
+    ) -> Vec<f64> {
+        let phases = if random_phase {
+            self.random_phases(partials.len(), self.seed)
+        } else {
+            vec![0.0; partials.len()]
+        };
+        let mut samples = self.sum_partials(partials, &phases, duration_secs);
+
+        if random_phase {
+            effects::normalize(&mut samples, AMPLITUDE);
+        }
+        samples
+    }
+
+    /// `count` starting phases drawn from the generator seeded with `seed`
+    fn random_phases(&self, count: usize, seed: u64) -> Vec<f64> {
+        let mut rng = self.rng.create(seed);
+        (0..count).map(|_| 2.0 * PI * rng.next_unit()).collect()
+    }
+
+    /// Sum weighted partials starting at `phases`, scaled by the total weight
+    fn sum_partials(
+        &self,
+        partials: &[(f64, f64)],
+        phases: &[f64],
+        duration_secs: f64,
+    ) -> Vec<f64> {
+        let num_samples = self.sample_count(duration_secs);
+        let scale = 1.0 / partials.iter().map(|&(_, weight)| weight).sum::<f64>();
 
         (0..num_samples)
             .map(|i| {
                 let t = i as f64 / self.config.sample_rate as f64;
-                let sum: f64 = frequencies
+                let sum: f64 = partials
                     .iter()
-                    .map(|&freq| (2.0 * PI * freq * t).sin())
+                    .zip(phases)
+                    .map(|(&(freq, weight), &phase)| weight * (2.0 * PI * freq * t + phase).sin())
                     .sum();
                 AMPLITUDE * sum * scale
             })
             .collect()
     }
 
+    /// Layer partials at the phase set whose crest factor best suits `target_db`
+    ///
+    /// Aligned phases and several seeded random draws are compared over the
+    /// first second; the lowest crest factor still at or above the target is
+    /// kept so [`Self::limit_to_crest`] has the least work to do. When every
+    /// candidate falls short, the peakiest one is used.
+    fn layer_for_crest(
+        &self,
+        partials: &[(f64, f64)],
+        duration_secs: f64,
+        target_db: f64,
+    ) -> Vec<f64> {
+        const RANDOM_CANDIDATES: u64 = 8;
+
+        let probe_secs = duration_secs.min(1.0);
+        let candidates = std::iter::once(vec![0.0; partials.len()]).chain(
+            (0..RANDOM_CANDIDATES)
+                .map(|k| self.random_phases(partials.len(), self.seed.wrapping_add(k))),
+        );
+        let scored: Vec<(f64, Vec<f64>)> = candidates
+            .map(|phases| {
+                let probe = self.sum_partials(partials, &phases, probe_secs);
+                (analysis::crest_factor_db(&probe), phases)
+            })
+            .collect();
+
+        let (_, phases) = scored
+            .iter()
+            .filter(|(crest, _)| *crest >= target_db)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .or_else(|| scored.iter().max_by(|a, b| a.0.total_cmp(&b.0)))
+            .expect("at least one phase candidate");
+
+        let mut samples = self.sum_partials(partials, phases, duration_secs);
+        effects::normalize(&mut samples, AMPLITUDE);
+        samples
+    }
+
+    /// Pull peaks down with the limiter until the crest factor is `target_db`
+    ///
+    /// The ceiling is found by bisection, since a lower ceiling always lowers
+    /// the peak-to-RMS ratio. The result is renormalized to full amplitude.
+    /// Returns the crest factor actually reached, which is higher than the
+    /// target only when the input was already below it.
+    fn limit_to_crest(&self, samples: &mut [f64], target_db: f64) -> f64 {
+        let initial = analysis::crest_factor_db(samples);
+        if initial <= target_db {
+            return initial;
+        }
+
+        let peak = samples.iter().fold(0.0f64, |acc, s| acc.max(s.abs()));
+        let limited = |ceiling: f64| {
+            let mut out = samples.to_vec();
+            effects::apply_limiter(&mut out, self.config.sample_rate, ceiling);
+            out
+        };
+
+        let (mut low, mut high) = (0.0, peak);
+        for _ in 0..30 {
+            let mid = 0.5 * (low + high);
+            if analysis::crest_factor_db(&limited(mid)) > target_db {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+
+        let mut out = limited(low.max(peak * 1e-6));
+        effects::normalize(&mut out, AMPLITUDE);
+        samples.copy_from_slice(&out);
+        analysis::crest_factor_db(samples)
+    }
+
+    /// Layer `partials` for a layer or comb file, honoring `crest_factor` when set
+    ///
+    /// The fade is applied before limiting so it counts toward the measured crest factor.
+    fn layer_shaped(
+        &self,
+        partials: &[(f64, f64)],
+        random_phase: bool,
+        fade_secs: Option<f64>,
+    ) -> Vec<f64> {
+        let Some(target_db) = self.crest_factor else {
+            let mut samples = self.layer_partials(partials, self.duration, random_phase);
+            if let Some(secs) = fade_secs {
+                self.apply_fade(&mut samples, secs);
+            }
+            return samples;
+        };
+
+        let mut samples = self.layer_for_crest(partials, self.duration, target_db);
+        if let Some(secs) = fade_secs {
+            self.apply_fade(&mut samples, secs);
+        }
+        let reached = self.limit_to_crest(&mut samples, target_db);
+        if reached > target_db + 0.1 {
+            println!(
+                "  Crest factor: {:.2} dB (the requested {:.2} dB is below what limiting can reach)",
+                reached, target_db
+            );
+        } else if reached < target_db - 0.1 {
+            println!(
+                "  Crest factor: {:.2} dB (no phase arrangement reaches {:.2} dB)",
+                reached, target_db
+            );
+        } else {
+            println!("  Crest factor: {:.2} dB", reached);
+        }
+        samples
+    }
+
+    /// `count` frequencies from `start` to `end` with a constant ratio between neighbors
+    pub fn log_spaced(start: f64, end: f64, count: usize) -> Vec<f64> {
+        if count < 2 {
+            return vec![start; count];
+        }
+        let ratio = end / start;
+        (0..count)
+            .map(|i| start * ratio.powf(i as f64 / (count - 1) as f64))
+            .collect()
+    }
+
+    /// Build a 1/n harmonic series of the fundamental plus `count` harmonics
+    ///
+    /// Partial n sits at `f * n * sqrt(1 + B * n^2)`, the stiff-string
+    /// inharmonicity formula: B = 0 gives exact multiples, and larger B
+    /// stretches the upper partials sharp for a bell-like tone. Harmonics at
+    /// or above Nyquist are excluded since they would alias.
+    pub fn harmonic_series(
+        &self,
+        fundamental: f64,
+        count: usize,
+        inharmonicity: f64,
+    ) -> Vec<(f64, f64)> {
+        let nyquist = self.config.sample_rate as f64 / 2.0;
+        (1..=count + 1)
+            .map(|n| {
+                let n = n as f64;
+                let stretch = (1.0 + inharmonicity * n * n).sqrt();
+                (fundamental * n * stretch, 1.0 / n)
+            })
+            .filter(|&(freq, _)| freq < nyquist)
+            .collect()
+    }
+
+    /// Generate layered frequencies split across stereo channels
+    ///
+    /// Frequencies are assigned round-robin, starting with the left channel,
+    /// and each channel is scaled by the number of tones routed to it.
+    pub fn generate_split_layers(&self, frequencies: &[f64], duration_secs: f64) -> Vec<[f64; 2]> {
+        let channels = self.generate_channel_layers(frequencies, 2, duration_secs);
+        channels[0]
+            .iter()
+            .zip(&channels[1])
+            .map(|(&left, &right)| [left, right])
+            .collect()
+    }
+
+    /// Route frequencies round-robin across `channels` outputs
+    ///
+    /// Each channel is scaled by the number of tones it carries.
+    pub fn generate_channel_layers(
+        &self,
+        frequencies: &[f64],
+        channels: u16,
+        duration_secs: f64,
+    ) -> Vec<Vec<f64>> {
+        let num_samples = self.sample_count(duration_secs);
+        let mut outputs = vec![vec![0.0; num_samples]; channels as usize];
+        let mut counts = vec![0usize; channels as usize];
+
+        for (idx, &freq) in frequencies.iter().enumerate() {
+            let channel = idx % channels as usize;
+            counts[channel] += 1;
+            for (out, sample) in outputs[channel]
+                .iter_mut()
+                .zip(self.generate_sine_wave(freq, duration_secs))
+            {
+                *out += sample;
+            }
+        }
+
+        for (output, count) in outputs.iter_mut().zip(counts) {
+            let scale = 1.0 / count.max(1) as f64;
+            output.iter_mut().for_each(|s| *s *= scale);
+        }
+        outputs
+    }
+
+    /// Generate every tone at once, each placed at its own stereo position
+    ///
+    /// `voices` pairs a mono buffer with a pan position from -1.0 (left) to
+    /// 1.0 (right); the panned voices are summed and scaled by the voice count.
+    pub fn mix_panned(&self, voices: &[(Vec<f64>, f64)]) -> Vec<[f64; 2]> {
+        let len = voices.iter().map(|(v, _)| v.len()).max().unwrap_or(0);
+        let scale = 1.0 / voices.len().max(1) as f64;
+        let mut out = vec![[0.0; 2]; len];
+
+        for (samples, pan) in voices {
+            let [left, right] = pan_gains(*pan);
+            for (frame, &sample) in out.iter_mut().zip(samples) {
+                frame[0] += sample * left * scale;
+                frame[1] += sample * right * scale;
+            }
+        }
+        out
+    }
+
+    /// Generate a tone by reading a single-cycle wavetable at the given pitch
+    ///
+    /// The table is stepped through at `frequency * len / sample_rate` entries
+    /// per sample with linear interpolation between neighbours.
+    pub fn generate_wavetable(
+        &self,
+        table: &[f64],
+        frequency: f64,
+        duration_secs: f64,
+    ) -> Vec<f64> {
+        let num_samples = self.sample_count(duration_secs);
+        let len = table.len();
+        if len == 0 {
+            return vec![0.0; num_samples];
+        }
+
+        let peak = table.iter().fold(0.0f64, |acc, s| acc.max(s.abs()));
+        let gain = if peak > 0.0 { AMPLITUDE / peak } else { 0.0 };
+        let step = frequency * len as f64 / self.config.sample_rate as f64;
+        let mut position = 0.0f64;
+
+        (0..num_samples)
+            .map(|_| {
+                let index = position as usize;
+                let frac = position - index as f64;
+                let current = table[index % len];
+                let next = table[(index + 1) % len];
+                position = (position + step) % len as f64;
+                gain * (current + (next - current) * frac)
+            })
+            .collect()
+    }
+
     /// Generate a singing bowl simulation with inharmonic partials
-    pub fn generate_singing_bowl(&self, frequency: f64, duration_secs: f64) -> Vec<f64> {
-        let num_samples = (self.config.sample_rate as f64 * duration_secs) as usize;
+    ///
+    /// `decay_secs` is the e-folding time of the amplitude decay; when absent
+    /// it defaults to 70% of the duration.
+    pub fn generate_singing_bowl(
+        &self,
+        frequency: f64,
+        duration_secs: f64,
+        decay_secs: Option<f64>,
+    ) -> Vec<f64> {
+        let num_samples = self.sample_count(duration_secs);
         let beat_freq = 0.5;
+        let decay_time = decay_secs.unwrap_or(duration_secs * 0.7);
 
         (0..num_samples)
             .map(|i| {
@@ -136,7 +719,7 @@ impl AudioGenerator {
                 let partial4 = 0.2 * (2.0 * PI * frequency * 4.07 * t).sin();
                 let partial5 = 0.1 * (2.0 * PI * frequency * 5.12 * t).sin();
 
-                let decay = (-t / (duration_secs * 0.7)).exp();
+                let decay = (-t / decay_time).exp();
                 let attack = if t < 0.01 { t / 0.01 } else { 1.0 };
 
                 let wave = (fundamental + partial2 + partial3 + partial4 + partial5) / 2.25;
@@ -146,13 +729,15 @@ impl AudioGenerator {
     }
 
     /// Generate a logarithmic frequency sweep
+    ///
+    /// Equal endpoints give a steady tone rather than the 0/0 of the log phase.
     pub fn generate_frequency_sweep(
         &self,
         start_freq: f64,
         end_freq: f64,
         duration_secs: f64,
     ) -> Vec<f64> {
-        let num_samples = (self.config.sample_rate as f64 * duration_secs) as usize;
+        let num_samples = self.sample_count(duration_secs);
         let freq_ratio = end_freq / start_freq;
         let ln_ratio = freq_ratio.ln();
 
@@ -160,44 +745,102 @@ impl AudioGenerator {
             .map(|i| {
                 let t = i as f64 / self.config.sample_rate as f64;
                 let progress = t / duration_secs;
-                let phase =
+                let phase = if ln_ratio == 0.0 {
+                    2.0 * PI * start_freq * t
+                } else {
                     2.0 * PI * start_freq * duration_secs * (freq_ratio.powf(progress) - 1.0)
-                        / ln_ratio;
+                        / ln_ratio
+                };
                 AMPLITUDE * phase.sin()
             })
             .collect()
     }
 
+    /// Generate a linear pitch glide, rendered chunk by chunk
+    ///
+    /// The phase is carried in a `PhaseState`, so the chunked output is
+    /// identical to rendering the whole glide at once.
+    pub fn generate_glide(&self, start_freq: f64, end_freq: f64, duration_secs: f64) -> Vec<f64> {
+        const CHUNK_SAMPLES: usize = 4096;
+
+        let num_samples = self.sample_count(duration_secs);
+        let glide = Glide {
+            start_freq,
+            end_freq,
+            total_samples: num_samples as u64,
+            sample_rate: self.config.sample_rate,
+        };
+
+        let mut state = PhaseState::default();
+        let mut samples = vec![0.0; num_samples];
+        for chunk in samples.chunks_mut(CHUNK_SAMPLES) {
+            glide.render(&mut state, chunk);
+        }
+        self.apply_fade(&mut samples, 0.05);
+        samples
+    }
+
+    /// Generate a fixed-frequency tone of any basic waveform
+    pub fn generate_waveform(
+        &self,
+        waveform: Waveform,
+        frequency: f64,
+        duration_secs: f64,
+    ) -> Vec<f64> {
+        let num_samples = self.sample_count(duration_secs);
+        let step = frequency / self.config.sample_rate as f64;
+        (0..num_samples)
+            .map(|i| AMPLITUDE * waveform.sample((i as f64 * step).rem_euclid(1.0)))
+            .collect()
+    }
+
+    /// Crossfade linearly from one waveform to another over the duration
+    ///
+    /// Both shapes share one phase, so the first sample is pure `from` and
+    /// the last is pure `to`.
+    pub fn generate_morph(
+        &self,
+        frequency: f64,
+        from: Waveform,
+        to: Waveform,
+        duration_secs: f64,
+    ) -> Vec<f64> {
+        let start = self.generate_waveform(from, frequency, duration_secs);
+        let end = self.generate_waveform(to, frequency, duration_secs);
+        let last = start.len().saturating_sub(1).max(1) as f64;
+        start
+            .iter()
+            .zip(&end)
+            .enumerate()
+            .map(|(i, (a, b))| {
+                let mix = i as f64 / last;
+                a * (1.0 - mix) + b * mix
+            })
+            .collect()
+    }
+
     /// Generate white noise using LCG
     pub fn generate_white_noise(&self, duration_secs: f64) -> Vec<f64> {
-        let num_samples = (self.config.sample_rate as f64 * duration_secs) as usize;
-        let mut seed: u64 = 12345;
+        let num_samples = self.sample_count(duration_secs);
+        let mut rng = self.rng.create(self.seed);
 
         (0..num_samples)
-            .map(|_| {
-                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
-                let random = ((seed >> 16) & 0x7FFF) as f64 / 32767.0 * 2.0 - 1.0;
-                AMPLITUDE * random * 0.7
-            })
+            .map(|_| AMPLITUDE * rng.next_bipolar() * 0.7)
             .collect()
     }
 
     /// Generate pink noise using Voss-McCartney algorithm
     pub fn generate_pink_noise(&self, duration_secs: f64) -> Vec<f64> {
-        let num_samples = (self.config.sample_rate as f64 * duration_secs) as usize;
-        let mut seed: u64 = 12345;
+        let num_samples = self.sample_count(duration_secs);
+        let mut rng = self.rng.create(self.seed);
         let mut octaves = [0.0f64; 16];
 
         (0..num_samples)
             .map(|i| {
-                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
-                let white = ((seed >> 16) & 0x7FFF) as f64 / 32767.0 * 2.0 - 1.0;
-
-                let mut sum = white;
+                let mut sum = rng.next_bipolar();
                 for (j, octave) in octaves.iter_mut().enumerate() {
                     if (i >> j) & 1 != ((i.wrapping_sub(1)) >> j) & 1 {
-                        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
-                        *octave = ((seed >> 16) & 0x7FFF) as f64 / 32767.0 * 2.0 - 1.0;
+                        *octave = rng.next_bipolar();
                     }
                     sum += *octave;
                 }
@@ -209,439 +852,4635 @@ impl AudioGenerator {
 
     /// Generate brown (Brownian) noise
     pub fn generate_brown_noise(&self, duration_secs: f64) -> Vec<f64> {
-        let num_samples = (self.config.sample_rate as f64 * duration_secs) as usize;
-        let mut seed: u64 = 12345;
+        let num_samples = self.sample_count(duration_secs);
+        let mut rng = self.rng.create(self.seed);
         let mut last = 0.0f64;
 
         (0..num_samples)
             .map(|_| {
-                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
-                let white = ((seed >> 16) & 0x7FFF) as f64 / 32767.0 * 2.0 - 1.0;
-                last = (last + white * 0.02).clamp(-1.0, 1.0);
+                last = (last + rng.next_bipolar() * 0.02).clamp(-1.0, 1.0);
                 AMPLITUDE * last * 0.7
             })
             .collect()
     }
 
-    /// Generate a drone with slow modulation
-    pub fn generate_drone(&self, frequencies: &[f64], duration_secs: f64) -> Vec<f64> {
-        let num_samples = (self.config.sample_rate as f64 * duration_secs) as usize;
-        let fade_samples = (self.config.sample_rate as f64 * 3.0) as usize;
-        let freq_count = frequencies.len() as f64;
-
-        (0..num_samples)
-            .map(|i| {
-                let t = i as f64 / self.config.sample_rate as f64;
+    /// Generate fractional Brownian motion with the given Hurst exponent
+    ///
+    /// The spectrum falls as 1/f^(2H+1): 0.5 is brown noise, lower values
+    /// approach pink and higher ones are smoother. Below 0.5 a bank of
+    /// octave-spaced one-pole low-passes, weighted by corner frequency, shapes
+    /// white noise directly; from 0.5 up the bank produces the increments
+    /// (fractional Gaussian noise), which a leaky integrator then sums.
+    pub fn generate_fractional_noise(&self, hurst: f64, duration_secs: f64) -> Vec<f64> {
+        let num_samples = self.sample_count(duration_secs);
+        let rate = self.config.sample_rate as f64;
+        let (exponent, integrate) = if hurst < 0.5 {
+            (2.0 * hurst + 1.0, false)
+        } else {
+            (2.0 * hurst - 1.0, true)
+        };
 
-                let sum: f64 = frequencies
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, &freq)| {
-                        let detune = 1.0 + (idx as f64 * 0.001);
-                        let mod_rate = 0.1 + idx as f64 * 0.03;
-                        let amp = 1.0 + 0.15 * (2.0 * PI * mod_rate * t).sin();
-                        amp * (2.0 * PI * freq * detune * t).sin()
-                    })
-                    .sum();
+        // (pole, gain, state) for corners from Nyquist down to about 1 Hz
+        let mut bank: Vec<(f64, f64, f64)> = (0..)
+            .map(|octave| rate / 2.0 / 2f64.powi(octave))
+            .take_while(|&corner| corner >= 1.0)
+            .map(|corner| {
+                let pole = (-2.0 * PI * corner / rate).exp();
+                (pole, corner.powf(-exponent / 2.0), 0.0)
+            })
+            .collect();
 
-                let envelope = compute_fade_envelope(i, num_samples, fade_samples);
-                AMPLITUDE * sum * envelope / freq_count
+        let leak = 1.0 - 2.0 * PI / rate;
+        let mut rng = self.rng.create(self.seed);
+        let mut integral = 0.0;
+        let mut samples: Vec<f64> = (0..num_samples)
+            .map(|_| {
+                let mut value = 0.0;
+                for (pole, gain, state) in bank.iter_mut() {
+                    *state = *pole * *state + (1.0 - *pole) * rng.next_bipolar();
+                    value += *gain * *state;
+                }
+                if integrate {
+                    integral = integral * leak + value;
+                    integral
+                } else {
+                    value
+                }
             })
-            .collect()
-    }
+            .collect();
 
-    /// Apply fade in/out to samples in place
-    pub fn apply_fade(&self, samples: &mut [f64], fade_duration_secs: f64) {
-        let fade_samples = (self.config.sample_rate as f64 * fade_duration_secs) as usize;
-        let fade_samples = fade_samples.min(samples.len() / 2);
+        effects::normalize(&mut samples, AMPLITUDE * 0.7);
+        samples
+    }
 
-        for (i, sample) in samples.iter_mut().take(fade_samples).enumerate() {
-            *sample *= i as f64 / fade_samples as f64;
+    /// Generate noise of the given color
+    pub fn generate_noise(&self, color: NoiseColor, duration_secs: f64) -> Vec<f64> {
+        match color {
+            NoiseColor::White => self.generate_white_noise(duration_secs),
+            NoiseColor::Pink => self.generate_pink_noise(duration_secs),
+            NoiseColor::Brown => self.generate_brown_noise(duration_secs),
         }
+    }
 
-        for (i, sample) in samples.iter_mut().rev().take(fade_samples).enumerate() {
-            *sample *= i as f64 / fade_samples as f64;
+    /// Extract the amplitude envelope of a reference WAV, scaled to peak at 1.0
+    ///
+    /// The reference is downmixed, rectified and smoothed with a 10 ms attack
+    /// and 100 ms release, then resampled to the output rate.
+    pub fn load_amplitude_envelope(&self, path: &Path) -> Result<Vec<f64>, SpiritError> {
+        let (channels, rate, samples) = wav::read_wav(path)?;
+        let mono: Vec<f64> = samples
+            .chunks(channels.max(1) as usize)
+            .map(|frame| frame.iter().sum::<f64>() / frame.len() as f64)
+            .collect();
+
+        let mut envelope = effects::envelope_follower(&mono, rate, 0.01, 0.1);
+        let peak = envelope.iter().fold(0.0f64, |acc, &e| acc.max(e));
+        if peak == 0.0 {
+            return Err(invalid_input(format!(
+                "envelope reference {} is silent",
+                path.display()
+            )));
         }
+        envelope.iter_mut().for_each(|e| *e /= peak);
+
+        let step = rate as f64 / self.config.sample_rate as f64;
+        let len = (envelope.len() as f64 / step) as usize;
+        Ok((0..len)
+            .map(|i| envelope[((i as f64 * step) as usize).min(envelope.len() - 1)])
+            .collect())
     }
 
-    /// Save mono samples to a WAV file
-    pub fn save_mono_wav(&self, path: &PathBuf, samples: &[f64]) -> Result<(), hound::Error> {
-        let spec = WavSpec {
-            channels: 1,
-            sample_rate: self.config.sample_rate,
-            bits_per_sample: self.config.bit_depth,
-            sample_format: SampleFormat::Int,
+    /// Multiply by the loaded amplitude envelope, holding its last value past the end
+    fn apply_amplitude_envelope(&self, samples: &mut [f64], envelope: &[f64]) {
+        let Some(&last) = envelope.last() else {
+            return;
         };
-
-        let mut writer = WavWriter::create(path, spec)?;
-        write_samples(&mut writer, samples, self.config.bit_depth)?;
-        writer.finalize()?;
-        println!("  Saved: {}", path.display());
-        Ok(())
+        for (i, sample) in samples.iter_mut().enumerate() {
+            *sample *= envelope.get(i).copied().unwrap_or(last);
+        }
     }
 
-    /// Save stereo samples to a WAV file
-    pub fn save_stereo_wav(
-        &self,
-        path: &PathBuf,
-        samples: &[[f64; 2]],
-    ) -> Result<(), hound::Error> {
-        let spec = WavSpec {
-            channels: 2,
-            sample_rate: self.config.sample_rate,
-            bits_per_sample: self.config.bit_depth,
-            sample_format: SampleFormat::Int,
-        };
+    /// Add noise of the given color under the samples at `level`
+    pub fn mix_noise_into(&self, samples: &mut [f64], color: NoiseColor, level: f64) {
+        if level == 0.0 {
+            return;
+        }
 
-        let mut writer = WavWriter::create(path, spec)?;
-        write_stereo_samples(&mut writer, samples, self.config.bit_depth)?;
-        writer.finalize()?;
-        println!("  Saved: {}", path.display());
-        Ok(())
-    }
+        let duration = samples.len() as f64 / self.config.sample_rate as f64;
+        let noise: Vec<f64> = self
+            .generate_noise(color, duration)
+            .into_iter()
+            .map(|n| level * n)
+            .collect();
 
-    /// Generate all frequencies for a category
-    pub fn generate_category(&self, category: Category) -> Result<(), hound::Error> {
-        let dir = self.output_dir.join(category.dir_name());
-        fs::create_dir_all(&dir).ok();
+        if self.tone_auto_gain {
+            self.apply_sidechain_gain(samples, &noise);
+        }
+        for (sample, n) in samples.iter_mut().zip(noise) {
+            *sample += n;
+        }
+    }
 
-        println!("\n=== Generating {} ===", category.display_name());
+    /// Raise the tone's gain wherever the noise envelope gets louder
+    ///
+    /// An envelope follower on the noise drives the gain, so the tone keeps
+    /// roughly the same margin above the noise floor. Gain is capped at +12 dB.
+    fn apply_sidechain_gain(&self, samples: &mut [f64], noise: &[f64]) {
+        const MAX_GAIN: f64 = 4.0;
 
-        for freq_info in category.frequencies() {
-            self.generate_frequency_file(&dir, category.file_prefix(), freq_info)?;
+        let tone_rms = analysis::rms(samples);
+        if tone_rms == 0.0 {
+            return;
         }
 
-        Ok(())
+        let envelope = effects::envelope_follower(noise, self.config.sample_rate, 0.05, 0.5);
+        for (sample, env) in samples.iter_mut().zip(envelope) {
+            *sample *= (1.0 + env / tone_rms).min(MAX_GAIN);
+        }
     }
 
-    /// Generate a single frequency file
-    fn generate_frequency_file(
-        &self,
-        dir: &std::path::Path,
-        prefix: &str,
-        freq_info: &FrequencyInfo,
-    ) -> Result<(), hound::Error> {
-        if freq_info.hz == 0.0 {
-            return Ok(()); // Skip zero-frequency entries like The Fool tarot
+    /// `n` short beeps for counting by ear; zero is one long, lower beep
+    ///
+    /// Each beep has a 5 ms raised-cosine edge so it reads as one clean
+    /// transient, and is followed by a gap as long as itself.
+    pub fn count_beeps(&self, n: u32) -> Vec<f64> {
+        const EDGE_SECS: f64 = 0.005;
+
+        let rate = self.config.sample_rate as f64;
+        let beep = |hz: f64, secs: f64| -> Vec<f64> {
+            let mut samples = self.generate_sine_wave(hz, secs);
+            let len = samples.len();
+            let edge = ((EDGE_SECS * rate) as usize).clamp(1, len / 2 + 1);
+            for (i, sample) in samples.iter_mut().enumerate() {
+                let distance = i.min(len - 1 - i);
+                if distance < edge {
+                    *sample *= 0.5 * (1.0 - (PI * distance as f64 / edge as f64).cos());
+                }
+            }
+            samples.resize(2 * len, 0.0);
+            samples
+        };
+
+        if n == 0 {
+            beep(440.0, ZERO_BEEP_SECS)
+        } else {
+            (0..n).flat_map(|_| beep(880.0, BEEP_SECS)).collect()
         }
+    }
 
-        println!("  {:.2} Hz: {}", freq_info.hz, freq_info.description);
+    /// Beeps counting the hundreds digit, then the tens digit, then a pause
+    fn beep_prefix_for(&self, hz: f64) -> Vec<f64> {
+        let whole = hz.trunc() as u64;
+        let pause = vec![0.0; (BEEP_PAUSE_SECS * self.config.sample_rate as f64) as usize];
 
-        let filename = format!("{}_{}_{:.2}hz.wav", prefix, freq_info.name, freq_info.hz);
-        let path = dir.join(filename);
+        let mut samples = self.count_beeps((whole / 100 % 10) as u32);
+        samples.extend_from_slice(&pause);
+        samples.extend(self.count_beeps((whole / 10 % 10) as u32));
+        samples.extend_from_slice(&pause);
+        samples.extend_from_slice(&pause);
+        samples
+    }
 
-        // Use isochronic tone for sub-audible frequencies
-        let samples = if freq_info.hz < 20.0 {
-            self.generate_isochronic_tone(200.0, freq_info.hz, self.duration)
-        } else {
-            self.generate_sine_wave(freq_info.hz, self.duration)
+    /// Length of [`Self::beep_prefix_for`] in samples, without rendering it
+    fn beep_prefix_len(&self, hz: f64) -> usize {
+        let whole = hz.trunc() as u64;
+        let beeps = |n: u64| match n {
+            0 => 2 * self.sample_count(ZERO_BEEP_SECS),
+            n => n as usize * 2 * self.sample_count(BEEP_SECS),
         };
-
-        self.save_mono_wav(&path, &samples)
+        let pause = (BEEP_PAUSE_SECS * self.config.sample_rate as f64) as usize;
+        beeps(whole / 100 % 10) + beeps(whole / 10 % 10) + 3 * pause
     }
 
-    /// Generate binaural beats for all brainwave states
-    pub fn generate_binaural_set(&self, base_freq: f64) -> Result<(), hound::Error> {
-        let dir = self.output_dir.join("binaural");
-        fs::create_dir_all(&dir).ok();
+    /// Gate samples on and off periodically with short raised-cosine ramps
+    pub fn apply_gate(&self, samples: &mut [f64], on_secs: f64, off_secs: f64) {
+        const RAMP_SECS: f64 = 0.01;
 
-        println!("\n=== Generating Binaural Beat Presets ===");
-        println!("(Use headphones for binaural beats to work!)");
+        let rate = self.config.sample_rate as f64;
+        let period = on_secs + off_secs;
+        let ramp = RAMP_SECS.min(on_secs / 2.0);
 
-        for state in BRAINWAVE_STATES {
-            self.generate_binaural_state(&dir, base_freq, state)?;
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let pos = (i as f64 / rate) % period;
+            let gain = if pos >= on_secs {
+                0.0
+            } else if pos < ramp {
+                FadeWindow::Hann.ramp(pos / ramp)
+            } else if pos > on_secs - ramp {
+                FadeWindow::Hann.ramp((on_secs - pos) / ramp)
+            } else {
+                1.0
+            };
+            *sample *= gain;
         }
-
-        Ok(())
     }
 
-    fn generate_binaural_state(
-        &self,
-        dir: &std::path::Path,
-        base_freq: f64,
-        state: &BrainwaveState,
-    ) -> Result<(), hound::Error> {
-        let target_freq = (state.low_hz + state.high_hz) / 2.0;
-        println!(
-            "  {} ({} Hz): {}",
-            state.name.to_uppercase(),
-            target_freq,
-            state.description
-        );
+    /// Apply a slow sinusoidal amplitude swell over the whole buffer
+    ///
+    /// Gain starts at full level and dips by `depth` (0-1) once per `period_secs`.
+    pub fn apply_swell(&self, samples: &mut [f64], period_secs: f64, depth: f64) {
+        let depth = depth.clamp(0.0, 1.0);
+        let rate = self.config.sample_rate as f64;
 
-        let samples = self.generate_binaural_beat(base_freq, target_freq, self.duration);
-        let path = dir.join(format!("binaural_{}_{:.1}hz.wav", state.name, target_freq));
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let phase = 2.0 * PI * (i as f64 / rate) / period_secs;
+            *sample *= 1.0 - depth * 0.5 * (1.0 - phase.cos());
+        }
+    }
+
+    /// Generate a drone with slow modulation
+    pub fn generate_drone(&self, frequencies: &[f64], duration_secs: f64) -> Vec<f64> {
+        let voices: Vec<(f64, f64)> = frequencies.iter().map(|&f| (f, 1.0)).collect();
+        self.generate_drone_weighted(&voices, duration_secs)
+    }
+
+    /// Generate a drone from `(frequency, weight)` voices, normalized by total weight
+    pub fn generate_drone_weighted(&self, voices: &[(f64, f64)], duration_secs: f64) -> Vec<f64> {
+        let num_samples = self.sample_count(duration_secs);
+        let fade_samples = self.fade_samples(num_samples, 3.0);
+        let total_weight: f64 = voices.iter().map(|&(_, weight)| weight).sum();
+
+        (0..num_samples)
+            .map(|i| {
+                let t = i as f64 / self.config.sample_rate as f64;
+
+                let sum: f64 = voices
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, &(freq, weight))| {
+                        let detune = 1.0 + (idx as f64 * 0.001);
+                        let mod_rate = 0.1 + idx as f64 * 0.03;
+                        let amp = 1.0 + 0.15 * (2.0 * PI * mod_rate * t).sin();
+                        weight * amp * (2.0 * PI * freq * detune * t).sin()
+                    })
+                    .sum();
+
+                let envelope = self.fade_envelope(i, num_samples, fade_samples);
+                AMPLITUDE * sum * envelope / total_weight
+            })
+            .collect()
+    }
+
+    /// Apply fade in/out to samples in place
+    pub fn apply_fade(&self, samples: &mut [f64], fade_duration_secs: f64) {
+        let num_samples = samples.len();
+        let fade_samples = self.fade_samples(num_samples, fade_duration_secs);
+
+        for (i, sample) in samples.iter_mut().enumerate() {
+            *sample *= self.fade_envelope(i, num_samples, fade_samples);
+        }
+    }
+
+    /// Shape mono samples with the configured ADSR envelope, if any
+    fn apply_envelope(&self, samples: &mut [f64]) {
+        if let Some(adsr) = self.envelope {
+            adsr.apply(samples, self.config.sample_rate);
+        }
+    }
+
+    /// Shape stereo frames with the configured ADSR envelope, if any
+    fn apply_envelope_stereo(&self, frames: &mut [[f64; 2]]) {
+        if let Some(adsr) = self.envelope {
+            let len = frames.len();
+            for (i, frame) in frames.iter_mut().enumerate() {
+                let gain = adsr.gain(i, len, self.config.sample_rate);
+                frame[0] *= gain;
+                frame[1] *= gain;
+            }
+        }
+    }
+
+    /// Number of samples in `duration_secs` at the output rate
+    ///
+    /// Truncates by default; with `exact_duration` the count is rounded, so
+    /// the written length is always `round(rate * duration)` samples.
+    fn sample_count(&self, duration_secs: f64) -> usize {
+        let exact = self.config.sample_rate as f64 * duration_secs;
+        if self.exact_duration {
+            exact.round() as usize
+        } else {
+            exact as usize
+        }
+    }
+
+    /// Number of samples in each fade ramp for a buffer of `num_samples`
+    ///
+    /// The Tukey window derives its ramps from the flat center fraction rather
+    /// than from the requested fade duration.
+    fn fade_samples(&self, num_samples: usize, fade_duration_secs: f64) -> usize {
+        if self.no_fade {
+            return 0;
+        }
+
+        let fade_samples = match self.fade_window {
+            FadeWindow::Tukey => {
+                let taper = 1.0 - self.tukey_flat.clamp(0.0, 1.0);
+                (num_samples as f64 * taper / 2.0) as usize
+            }
+            FadeWindow::Linear | FadeWindow::Hann => {
+                (self.config.sample_rate as f64 * fade_duration_secs) as usize
+            }
+        };
+        fade_samples.min(num_samples / 2)
+    }
+
+    /// Compute fade envelope for sample at index i
+    fn fade_envelope(&self, i: usize, num_samples: usize, fade_samples: usize) -> f64 {
+        let position = if i < fade_samples {
+            i as f64 / fade_samples as f64
+        } else if i >= num_samples - fade_samples {
+            (num_samples - i) as f64 / fade_samples as f64
+        } else {
+            return 1.0;
+        };
+        self.fade_window.ramp(position)
+    }
+
+    /// Save mono samples to a WAV file
+    ///
+    /// With a ping-pong delay configured, the output is upgraded to stereo.
+    pub fn save_mono_wav(&self, path: &Path, samples: &[f64]) -> Result<(), SpiritError> {
+        self.save_mono(path, samples, false)
+    }
+
+    /// [`Self::save_mono_wav`], optionally running the effects chain oversampled
+    fn save_mono(&self, path: &Path, samples: &[f64], oversample: bool) -> Result<(), SpiritError> {
+        if let Some(rate) = self.rotate {
+            let frames = effects::apply_rotation(samples, self.config.sample_rate, rate);
+            return self.save_stereo(path, &frames, oversample);
+        }
+        if let Some(delay_ms) = self.haas {
+            let frames = effects::apply_haas(samples, self.config.sample_rate, delay_ms);
+            return self.save_stereo(path, &frames, oversample);
+        }
+        if let Some(width) = self.upmix {
+            let frames = effects::apply_upmix(samples, width);
+            return self.save_stereo(path, &frames, oversample);
+        }
+        if self.delay.is_some() {
+            let frames: Vec<[f64; 2]> = samples.iter().map(|&s| [s, s]).collect();
+            return self.save_stereo(path, &frames, oversample);
+        }
+        self.write_wav_oversampled(path, 1, samples, oversample)
+    }
+
+    /// Save stereo samples to a WAV file
+    ///
+    /// With `force_mono` set the frames are averaged into one channel, which
+    /// turns a binaural beat into an ordinary (monaural) beat.
+    pub fn save_stereo_wav(&self, path: &Path, samples: &[[f64; 2]]) -> Result<(), SpiritError> {
+        self.save_stereo(path, samples, false)
+    }
+
+    /// [`Self::save_stereo_wav`], optionally running the effects chain oversampled
+    fn save_stereo(
+        &self,
+        path: &Path,
+        samples: &[[f64; 2]],
+        oversample: bool,
+    ) -> Result<(), SpiritError> {
+        if self.force_mono {
+            return self.save_mono(path, &downmix(samples), oversample);
+        }
+        let delayed;
+        let samples = match self.delay {
+            Some(delay_secs) => {
+                delayed = effects::apply_pingpong_delay(
+                    samples,
+                    self.config.sample_rate,
+                    delay_secs,
+                    self.delay_feedback,
+                    self.delay_mix,
+                );
+                &delayed
+            }
+            None => samples,
+        };
+        let widened;
+        let samples = if self.width == 1.0 {
+            samples
+        } else {
+            widened = apply_stereo_width(samples, self.width);
+            &widened
+        };
+
+        let [left_gain, right_gain] = self.balance_gains();
+        if self.ms_split {
+            return self.save_mid_side(path, samples, [left_gain, right_gain], oversample);
+        }
+        let interleaved: Vec<f64> = samples
+            .iter()
+            .flat_map(|&[left, right]| {
+                let (left, right) = (left * left_gain, right * right_gain);
+                if self.swap_channels {
+                    [right, left]
+                } else {
+                    [left, right]
+                }
+            })
+            .collect();
+        self.write_wav_oversampled(path, 2, &interleaved, oversample)
+    }
+
+    /// Write stereo frames as `<name>_mid.wav` and `<name>_side.wav`
+    ///
+    /// Mid = (L + R) / √2 and Side = (L - R) / √2, which keeps total energy
+    /// unchanged; a centered signal lands entirely in Mid.
+    fn save_mid_side(
+        &self,
+        path: &Path,
+        samples: &[[f64; 2]],
+        [left_gain, right_gain]: [f64; 2],
+        oversample: bool,
+    ) -> Result<(), SpiritError> {
+        let (mid, side): (Vec<f64>, Vec<f64>) = samples
+            .iter()
+            .map(|&[left, right]| {
+                let [mid, side] = to_mid_side([left * left_gain, right * right_gain]);
+                (mid, side)
+            })
+            .unzip();
+
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let mid_path = path.with_file_name(format!("{}_mid.wav", stem));
+        let side_path = path.with_file_name(format!("{}_side.wav", stem));
+        self.write_wav_oversampled(&mid_path, 1, &mid, oversample)?;
+        self.write_wav_oversampled(&side_path, 1, &side, oversample)
+    }
+
+    /// Per-channel gains for the configured balance
+    ///
+    /// Balance only attenuates the opposite channel, so centered output is
+    /// unchanged and a fully panned channel keeps unity gain.
+    fn balance_gains(&self) -> [f64; 2] {
+        let balance = self.balance.clamp(-1.0, 1.0);
+        [(1.0 - balance).min(1.0), (1.0 + balance).min(1.0)]
+    }
+
+    /// Write interleaved samples using the configured output format
+    fn write_wav(&self, path: &Path, channels: u16, samples: &[f64]) -> Result<(), SpiritError> {
+        self.write_wav_oversampled(path, channels, samples, false)
+    }
+
+    /// [`Self::write_wav`], running the effects chain 2x oversampled when `oversample` is set
+    fn write_wav_oversampled(
+        &self,
+        path: &Path,
+        channels: u16,
+        samples: &[f64],
+        oversample: bool,
+    ) -> Result<(), SpiritError> {
+        if self.is_cancelled() {
+            return Err(SpiritError::Cancelled);
+        }
+
+        // Catches what the up-front estimate cannot see, e.g. concatenated output
+        if let Some(limit) = self.max_file_bytes {
+            let frames = samples.len() / channels as usize;
+            let secs = frames as f64 / self.config.sample_rate as f64;
+            let bytes = self.config.estimated_wav_bytes(secs, channels);
+            if bytes > limit {
+                return Err(invalid_input(format!(
+                    "{} would be {:.1} MB, over the --max-file-size limit of {:.1} MB",
+                    path.display(),
+                    bytes as f64 / 1e6,
+                    limit as f64 / 1e6
+                )));
+            }
+        }
+
+        if self.resume && self.is_complete(path, channels, samples.len() / channels as usize) {
+            self.report_skipped(path);
+            return Ok(());
+        }
+
+        let processed;
+        let samples = if self.effects.is_empty()
+            && self.target_lufs.is_none()
+            && self.mix_noise.is_none()
+            && self.amplitude_envelope.is_none()
+        {
+            samples
+        } else {
+            processed = self.post_process(channels, samples, oversample);
+            &processed
+        };
+
+        // NaN/Inf from degenerate parameters would otherwise clamp to full scale
+        let sanitized;
+        let samples = match wav::sanitize(samples) {
+            Some((clean, count)) => {
+                self.replaced_samples.fetch_add(count, Ordering::Relaxed);
+                eprintln!(
+                    "  Warning: replaced {} non-finite sample(s) with silence",
+                    count
+                );
+                sanitized = clean;
+                &sanitized
+            }
+            None => samples,
+        };
+
+        if channels > 2 {
+            self.write_multichannel_wav(path, channels, samples)?;
+        } else if self.config.bit_depth == 64 {
+            self.write_f64_wav(path, channels, samples)?;
+        } else {
+            let spec = WavSpec {
+                channels,
+                sample_rate: self.config.sample_rate,
+                bits_per_sample: self.config.bit_depth,
+                sample_format: if self.config.float {
+                    SampleFormat::Float
+                } else {
+                    SampleFormat::Int
+                },
+            };
+
+            let mut writer = self.open_writer(path, spec)?;
+            match self
+                .dither
+                .filter(|_| spec.bits_per_sample == 16 && !self.config.float)
+            {
+                Some(dither) => {
+                    for sample in wav::dither_i16(samples, channels, dither, self.seed) {
+                        writer.write_sample(sample)?;
+                    }
+                }
+                None => wav::write_samples(&mut writer, samples, spec)?,
+            }
+            writer.finalize()?;
+        }
+
+        self.report_saved(path);
+        for &format in &self.also_formats {
+            let encoded = encode::encode(path, format)?;
+            self.report_saved(&encoded);
+        }
+        if self.preview_spectrum {
+            self.print_spectrum_preview(channels, samples);
+        }
+        Ok(())
+    }
+
+    /// Print a bar per log-spaced band showing where the energy sits
+    fn print_spectrum_preview(&self, channels: u16, samples: &[f64]) {
+        const BANDS: usize = 12;
+        const BAR_WIDTH: f64 = 40.0;
+
+        let mono: Vec<f64> = samples
+            .chunks(channels as usize)
+            .map(|frame| frame.iter().sum::<f64>() / channels as f64)
+            .collect();
+        let bands = analysis::band_energies(&mono, self.config.sample_rate, 20.0, BANDS);
+        let peak = bands.iter().fold(0.0f64, |acc, &(_, _, e)| acc.max(e));
+        if peak <= 0.0 {
+            return;
+        }
+
+        for (lo, hi, energy) in bands {
+            let bar = (energy / peak * BAR_WIDTH).round() as usize;
+            println!("    {:>6.0}-{:<6.0} Hz |{}", lo, hi, "#".repeat(bar));
+        }
+    }
+
+    /// Mix noise, run the effects chain, and normalize loudness over interleaved samples
+    fn post_process(&self, channels: u16, samples: &[f64], oversample: bool) -> Vec<f64> {
+        let mut split = deinterleave(samples, channels);
+        for channel in split.iter_mut() {
+            if let Some(envelope) = &self.amplitude_envelope {
+                self.apply_amplitude_envelope(channel, envelope);
+            }
+            if let Some(color) = self.mix_noise {
+                self.mix_noise_into(channel, color, self.noise_level);
+            }
+            if oversample {
+                effects::apply_chain_oversampled(channel, &self.effects, self.config.sample_rate);
+            } else {
+                effects::apply_chain(channel, &self.effects, self.config.sample_rate);
+            }
+        }
+        if let Some(target) = self.target_lufs {
+            self.normalize_loudness(&mut split, target);
+        }
+        interleave(&split)
+    }
+
+    /// Apply a uniform gain so the integrated loudness matches `target` LUFS
+    fn normalize_loudness(&self, channels: &mut [Vec<f64>], target: f64) {
+        let measured = analysis::integrated_loudness(channels, self.config.sample_rate);
+        if !measured.is_finite() {
+            return;
+        }
+
+        let gain = 10f64.powf((target - measured) / 20.0);
+        let peak = channels
+            .iter()
+            .flatten()
+            .fold(0.0f64, |acc, s| acc.max(s.abs()))
+            * gain;
+        println!(
+            "  Loudness: {:.1} LUFS -> {:.1} LUFS ({:+.1} dB)",
+            measured,
+            target,
+            20.0 * gain.log10()
+        );
+        if peak > 1.0 {
+            eprintln!(
+                "  Warning: loudness target clips (peak {:.2} dBFS)",
+                20.0 * peak.log10()
+            );
+        }
+
+        for sample in channels.iter_mut().flatten() {
+            *sample *= gain;
+        }
+    }
+
+    /// Write a 64-bit float file, prepending existing samples in append mode
+    fn write_f64_wav(
+        &self,
+        path: &Path,
+        channels: u16,
+        samples: &[f64],
+    ) -> Result<(), SpiritError> {
+        let rate = self.config.sample_rate;
+        if !self.append || !path.exists() {
+            return wav::write_f64_wav(path, channels, rate, samples);
+        }
+
+        let (existing_channels, existing_rate, mut existing) = wav::read_f64_wav(path)?;
+        if (existing_channels, existing_rate) != (channels, rate) {
+            return Err(unsupported_format(format!(
+                "cannot append to {}: existing format ({} ch, {} Hz) differs from requested ({} ch, {} Hz)",
+                path.display(),
+                existing_channels,
+                existing_rate,
+                channels,
+                rate
+            )));
+        }
+
+        existing.extend_from_slice(samples);
+        wav::write_f64_wav(path, channels, rate, &existing)
+    }
+
+    /// Write 3+ channel output with the speaker mask for its layout
+    fn write_multichannel_wav(
+        &self,
+        path: &Path,
+        channels: u16,
+        samples: &[f64],
+    ) -> Result<(), SpiritError> {
+        let rate = self.config.sample_rate;
+        let mask = ChannelLayout::for_channels(channels).map_or(0, ChannelLayout::mask);
+        let write = |samples: &[f64]| {
+            wav::write_extensible_wav(
+                path,
+                channels,
+                rate,
+                self.config.bit_depth,
+                self.config.float,
+                mask,
+                samples,
+            )
+        };
+
+        if !self.append || !path.exists() {
+            return write(samples);
+        }
+
+        let spec = WavReader::open(path)?.spec();
+        let existing_float = spec.sample_format == SampleFormat::Float;
+        if (
+            spec.channels,
+            spec.sample_rate,
+            spec.bits_per_sample,
+            existing_float,
+        ) != (channels, rate, self.config.bit_depth, self.config.float)
+        {
+            return Err(unsupported_format(format!(
+                "cannot append to {}: existing format ({} ch, {} Hz, {}-bit) differs from requested ({} ch, {} Hz, {}-bit)",
+                path.display(),
+                spec.channels,
+                spec.sample_rate,
+                spec.bits_per_sample,
+                channels,
+                rate,
+                self.config.bit_depth
+            )));
+        }
+
+        let (_, _, mut existing) = wav::read_wav(path)?;
+        existing.extend_from_slice(samples);
+        write(&existing)
+    }
+
+    /// Open a WAV writer, appending to an existing file when requested
+    ///
+    /// In append mode the existing file's format must match `spec` exactly,
+    /// otherwise the concatenated audio would be misinterpreted.
+    fn open_writer(
+        &self,
+        path: &Path,
+        spec: WavSpec,
+    ) -> Result<WavWriter<BufWriter<fs::File>>, SpiritError> {
+        if !self.append || !path.exists() {
+            return Ok(WavWriter::create(path, spec)?);
+        }
+
+        let existing = WavReader::open(path)?.spec();
+        if existing != spec {
+            return Err(unsupported_format(format!(
+                "cannot append to {}: existing format ({} ch, {} Hz, {}-bit) differs from requested ({} ch, {} Hz, {}-bit)",
+                path.display(),
+                existing.channels,
+                existing.sample_rate,
+                existing.bits_per_sample,
+                spec.channels,
+                spec.sample_rate,
+                spec.bits_per_sample
+            )));
+        }
+
+        Ok(WavWriter::append(path)?)
+    }
+
+    /// Print where a file was written
+    fn report_saved(&self, path: &Path) {
+        self.saved_files.fetch_add(1, Ordering::Relaxed);
+        self.record_written(path);
+        if self.append {
+            println!("  Appended: {}", path.display());
+        } else {
+            println!("  Saved: {}", path.display());
+        }
+    }
+
+    /// Whether `path` is already a WAV in the output format with `channels` and `frames` frames
+    ///
+    /// Channel count, sample rate, bit depth and integer/float format must all
+    /// match, so changing the output format re-renders instead of resuming.
+    fn is_complete(&self, path: &Path, channels: u16, frames: usize) -> bool {
+        if self.config.bit_depth == 64 {
+            // hound cannot open 64-bit float files
+            return wav::read_f64_wav(path).is_ok_and(|(existing_channels, rate, samples)| {
+                existing_channels == channels
+                    && rate == self.config.sample_rate
+                    && samples.len() == frames * channels as usize
+            });
+        }
+
+        let Ok(reader) = WavReader::open(path) else {
+            return false;
+        };
+        let expected = WavSpec {
+            channels,
+            sample_rate: self.config.sample_rate,
+            bits_per_sample: self.config.bit_depth,
+            sample_format: if self.config.float {
+                SampleFormat::Float
+            } else {
+                SampleFormat::Int
+            },
+        };
+        reader.spec() == expected && reader.duration() as usize == frames
+    }
+
+    /// Files, with their channel counts, that saving mono audio to `path` writes
+    ///
+    /// Mirrors [`Self::save_mono_wav`]: rotation, Haas, upmix and delay turn
+    /// the output stereo, and `ms_split` then writes `_mid` and `_side` files
+    /// instead of `path` itself.
+    fn mono_output_files(&self, path: &Path) -> Vec<(PathBuf, u16)> {
+        let stereo = self.rotate.is_some()
+            || self.haas.is_some()
+            || self.upmix.is_some()
+            || self.delay.is_some();
+        if !stereo {
+            return vec![(path.to_path_buf(), 1)];
+        }
+        if !self.ms_split {
+            return vec![(path.to_path_buf(), 2)];
+        }
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        ["mid", "side"]
+            .iter()
+            .map(|part| (path.with_file_name(format!("{}_{}.wav", stem, part)), 1))
+            .collect()
+    }
+
+    /// Record an existing file that `--resume` left in place
+    fn report_skipped(&self, path: &Path) {
+        self.record_written(path);
+        println!("  Skipped (already generated): {}", path.display());
+    }
+
+    /// Add a path to the output list unless it is already there
+    fn record_written(&self, path: &Path) {
+        let mut written = self.written.lock().unwrap();
+        if !written.iter().any(|p| p == path) {
+            written.push(path.to_path_buf());
+        }
+    }
+
+    /// Whether cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+
+    /// Number of files written so far
+    pub fn saved_files(&self) -> usize {
+        self.saved_files.load(Ordering::Relaxed)
+    }
+
+    /// Number of NaN/Inf samples written as silence so far
+    pub fn replaced_samples(&self) -> usize {
+        self.replaced_samples.load(Ordering::Relaxed)
+    }
+
+    /// Distinct paths written so far, in the order they were first written
+    pub fn written_files(&self) -> Vec<PathBuf> {
+        self.written.lock().unwrap().clone()
+    }
+
+    /// Database entries skipped because they would alias, as `(label, Hz)`
+    pub fn skipped_frequencies(&self) -> Vec<(String, f64)> {
+        self.skipped.lock().unwrap().clone()
+    }
+
+    /// Whether `hz` can be represented at the output sample rate; records it if not
+    fn within_nyquist(&self, label: &str, hz: f64) -> bool {
+        let nyquist = self.config.sample_rate as f64 / 2.0;
+        if hz < nyquist {
+            return true;
+        }
+        eprintln!(
+            "  Warning: skipping {} ({:.2} Hz is at or above Nyquist, {} Hz)",
+            label, hz, nyquist
+        );
+        self.skipped.lock().unwrap().push((label.to_string(), hz));
+        false
+    }
+
+    /// Generate all frequencies for a category
+    ///
+    /// With `antialias` set, the Rife category runs its effects chain
+    /// oversampled, since its tones reach 10 kHz where harmonics added by
+    /// nonlinear effects would fold back below Nyquist.
+    pub fn generate_category(&self, category: Category) -> Result<(), SpiritError> {
+        let oversample = self.antialias && category == Category::Rife;
+        let dir = self.output_dir.join(category.dir_name());
+        fs::create_dir_all(&dir).ok();
+
+        println!("\n=== Generating {} ===", category.display_name());
+
+        if self.concat && !self.both {
+            let (titles, segments) = self.category_segments(category);
+            return self.save_category_sequence(&dir, category, &titles, segments, oversample);
+        }
+
+        // With `both`, the tones already rendered for the files become the master
+        let mut titles = Vec::new();
+        let mut segments = Vec::new();
+        for freq_info in category.frequencies() {
+            if let Some(tone) =
+                self.generate_frequency_file(&dir, category.file_prefix(), freq_info, oversample)?
+            {
+                if self.both {
+                    titles.push(freq_info.name.to_string());
+                    segments.push(tone);
+                }
+            }
+        }
+        if self.both {
+            println!("  Concatenated master...");
+            self.save_category_sequence(&dir, category, &titles, segments, oversample)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a category's rendered tones back to back into a single file
+    fn save_category_sequence(
+        &self,
+        dir: &Path,
+        category: Category,
+        titles: &[String],
+        mut segments: Vec<Vec<f64>>,
+        oversample: bool,
+    ) -> Result<(), SpiritError> {
+        if self.match_loudness {
+            match_segment_rms(&mut segments);
+        }
+
+        let path = dir.join(format!("{}_sequence.wav", category.dir_name()));
+        self.save_sequence(
+            &path,
+            category.display_name(),
+            titles,
+            &segments,
+            oversample,
+        )?;
+        Ok(())
+    }
+
+    /// Render every category into one file, separated by `gap_secs` of silence
+    ///
+    /// Each category becomes one track (and one cue sheet entry) whose
+    /// trailing silence belongs to it, so track starts land on the first tone.
+    pub fn generate_all_categories_sequence(&self, gap_secs: f64) -> Result<(), SpiritError> {
+        fs::create_dir_all(&self.output_dir).ok();
+        println!(
+            "\n=== Generating All Categories ({} s between categories) ===",
+            gap_secs
+        );
+
+        let mut titles = Vec::new();
+        let mut categories = Vec::new();
+        for category in Category::all() {
+            println!("  {}", category.display_name());
+            let (_, segments) = self.category_segments(*category);
+            if !segments.is_empty() {
+                titles.push(category.display_name().to_string());
+                categories.push(segments);
+            }
+        }
+
+        if self.match_loudness {
+            let mut tones: Vec<Vec<f64>> = categories.iter().flatten().cloned().collect();
+            match_segment_rms(&mut tones);
+            let mut tones = tones.into_iter();
+            for segments in categories.iter_mut() {
+                for segment in segments.iter_mut() {
+                    *segment = tones.next().unwrap_or_default();
+                }
+            }
+        }
+
+        let gap = vec![0.0; self.sample_count(gap_secs)];
+        let last = categories.len().saturating_sub(1);
+        let tracks: Vec<Vec<f64>> = categories
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut segments)| {
+                if i < last {
+                    segments.push(gap.clone());
+                }
+                segments.concat()
+            })
+            .collect();
+
+        let path = self.output_dir.join("all_sequence.wav");
+        self.save_sequence(&path, "All Categories", &titles, &tracks, false)?;
+        Ok(())
+    }
+
+    /// Titles and rendered tones for a category's non-zero frequencies
+    fn category_segments(&self, category: Category) -> (Vec<String>, Vec<Vec<f64>>) {
+        category
+            .frequencies()
+            .iter()
+            .filter(|f| f.hz > 0.0 && self.within_nyquist(f.name, f.hz))
+            .map(|f| {
+                println!("  {:.2} Hz: {}", f.hz, f.description);
+                (f.name.to_string(), self.database_tone(f.hz, self.duration))
+            })
+            .unzip()
+    }
+
+    /// Concatenate segments into one mono file, returning each segment's start frame
+    ///
+    /// With `cue_sheet` set, a sibling `.cue` file lists one track per segment.
+    fn save_sequence(
+        &self,
+        path: &Path,
+        title: &str,
+        titles: &[String],
+        segments: &[Vec<f64>],
+        oversample: bool,
+    ) -> Result<Vec<(u32, String)>, SpiritError> {
+        let mut offset = 0;
+        let tracks: Vec<(u32, String)> = segments
+            .iter()
+            .zip(titles)
+            .map(|(segment, title)| {
+                let start = offset;
+                offset += segment.len();
+                (start as u32, title.clone())
+            })
+            .collect();
+
+        self.save_mono(path, &segments.concat(), oversample)?;
+        if self.cue_sheet {
+            let cue_path = path.with_extension("cue");
+            wav::write_cue_sheet(&cue_path, path, title, &tracks, self.config.sample_rate)?;
+            println!("  Cue sheet: {}", cue_path.display());
+        }
+        Ok(tracks)
+    }
+
+    /// Generate Solfeggio tones with digit roots and higher-octave variants
+    pub fn generate_solfeggio_extended(&self) -> Result<(), SpiritError> {
+        const EXTENDED_OCTAVES: i32 = 2;
+
+        let category = Category::Solfeggio;
+        let dir = self.output_dir.join(category.dir_name());
+        fs::create_dir_all(&dir).ok();
+
+        println!(
+            "\n=== Generating {} (Extended) ===",
+            category.display_name()
+        );
+
+        for freq_info in category.frequencies() {
+            let root = digit_root(freq_info.hz);
+            let marker = if root.is_multiple_of(3) {
+                ""
+            } else {
+                " (not 3/6/9)"
+            };
+            println!("  Digit root of {}: {}{}", freq_info.hz, root, marker);
+
+            for octave in 0..=EXTENDED_OCTAVES {
+                let variant = FrequencyInfo {
+                    hz: octave_transpose(freq_info.hz, octave),
+                    ..*freq_info
+                };
+                self.generate_frequency_file(&dir, category.file_prefix(), &variant, false)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generate a category once per tuning reference, each in its own subfolder
+    ///
+    /// Every frequency is scaled by `reference / baseline`, treating the
+    /// database values as tuned to `baseline` (normally A4 = 440 Hz).
+    pub fn generate_retuned_category(
+        &self,
+        category: Category,
+        references: &[f64],
+        baseline: f64,
+    ) -> Result<(), SpiritError> {
+        if let Some(bad) = references
+            .iter()
+            .chain(std::iter::once(&baseline))
+            .find(|&&hz| hz <= 0.0 || !hz.is_finite())
+        {
+            return Err(invalid_frequency(format!(
+                "tuning references must be positive (got {})",
+                bad
+            )));
+        }
+
+        let dir = self
+            .output_dir
+            .join(format!("{}_retuned", category.dir_name()));
+        println!(
+            "\n=== Generating {} Retuned from {} Hz ===",
+            category.display_name(),
+            baseline
+        );
+
+        for &reference in references {
+            let ratio = reference / baseline;
+            println!("  A4 = {} Hz (x{:.5})", reference, ratio);
+
+            let variant_dir = dir.join(format!("a{}", reference));
+            fs::create_dir_all(&variant_dir).ok();
+            for freq_info in category.frequencies() {
+                let variant = FrequencyInfo {
+                    hz: freq_info.hz * ratio,
+                    ..*freq_info
+                };
+                self.generate_frequency_file(
+                    &variant_dir,
+                    category.file_prefix(),
+                    &variant,
+                    false,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generate a single frequency file, returning its tone unless it was
+    /// left in place by `--resume` and no `--both` master needs it
+    fn generate_frequency_file(
+        &self,
+        dir: &std::path::Path,
+        prefix: &str,
+        freq_info: &FrequencyInfo,
+        oversample: bool,
+    ) -> Result<Option<Vec<f64>>, SpiritError> {
+        if freq_info.hz == 0.0 {
+            return Ok(None); // Skip zero-frequency entries like The Fool tarot
+        }
+        if !self.within_nyquist(freq_info.name, freq_info.hz) {
+            return Ok(None);
+        }
+
+        println!("  {:.2} Hz: {}", freq_info.hz, freq_info.description);
+
+        let filename = format!("{}.wav", self.frequency_filename(prefix, freq_info));
+        let path = dir.join(filename);
+
+        let prefix_frames = if self.beep_prefix {
+            self.beep_prefix_len(freq_info.hz)
+        } else {
+            0
+        };
+        let frames = self.sample_count(self.duration) + prefix_frames;
+        let outputs = self.mono_output_files(&path);
+        if self.resume
+            && outputs
+                .iter()
+                .all(|(file, channels)| self.is_complete(file, *channels, frames))
+        {
+            for (file, _) in &outputs {
+                self.report_skipped(file);
+            }
+            // Only a `--both` master still needs the tone of a file left in place
+            return Ok(self
+                .both
+                .then(|| self.database_tone(freq_info.hz, self.duration)));
+        }
+
+        let tone = self.database_tone(freq_info.hz, self.duration);
+        if self.beep_prefix {
+            let mut samples = self.beep_prefix_for(freq_info.hz);
+            samples.extend_from_slice(&tone);
+            self.save_mono(&path, &samples, oversample)?;
+        } else {
+            self.save_mono(&path, &tone, oversample)?;
+        }
+        Ok(Some(tone))
+    }
+
+    /// Render a database frequency, using an isochronic tone for sub-audible ones
+    fn database_tone(&self, hz: f64, duration_secs: f64) -> Vec<f64> {
+        if hz < 20.0 {
+            self.generate_isochronic_tone(200.0, hz, duration_secs)
+        } else {
+            self.generate_sine_wave(hz, duration_secs)
+        }
+    }
+
+    /// Expand the filename template for a database frequency
+    fn frequency_filename(&self, prefix: &str, freq_info: &FrequencyInfo) -> String {
+        let (note, _) = hz_to_note(freq_info.hz, self.tuning);
+        self.name_template
+            .replace("{prefix}", prefix)
+            .replace("{name}", freq_info.name)
+            .replace("{hz}", &format!("{:.2}", freq_info.hz))
+            .replace("{note}", &note)
+    }
+
+    /// Generate binaural beats for all brainwave states
+    pub fn generate_binaural_set(&self, base_freq: f64, edge: BandEdge) -> Result<(), SpiritError> {
+        let dir = self.output_dir.join("binaural");
+        fs::create_dir_all(&dir).ok();
+
+        println!("\n=== Generating Binaural Beat Presets ===");
+        println!("(Use headphones for binaural beats to work!)");
+
+        for state in BRAINWAVE_STATES {
+            self.generate_binaural_state(&dir, base_freq, state, edge)?;
+        }
+
+        Ok(())
+    }
+
+    fn generate_binaural_state(
+        &self,
+        dir: &std::path::Path,
+        base_freq: f64,
+        state: &BrainwaveState,
+        edge: BandEdge,
+    ) -> Result<(), SpiritError> {
+        let target_freq = edge.frequency(state);
+        println!(
+            "  {} ({} Hz): {}",
+            state.name.to_uppercase(),
+            target_freq,
+            state.description
+        );
+
+        let samples = self.generate_binaural_beat(base_freq, target_freq, self.duration);
+        let path = dir.join(format!("binaural_{}_{:.1}hz.wav", state.name, target_freq));
         self.save_stereo_wav(&path, &samples)
     }
 
-    /// Generate Schumann resonance (7.83 Hz)
-    pub fn generate_schumann(&self) -> Result<(), hound::Error> {
-        let dir = self.output_dir.join("schumann");
-        fs::create_dir_all(&dir).ok();
+    /// Generate a category as one stereo drone with each tone at its own pan position
+    pub fn generate_panorama(&self, category: Category) -> Result<(), SpiritError> {
+        let dir = self.output_dir.join("panorama");
+        fs::create_dir_all(&dir).ok();
+
+        println!("\n=== Generating {} Panorama ===", category.display_name());
+
+        let tones: Vec<&FrequencyInfo> = category
+            .frequencies()
+            .iter()
+            .filter(|f| f.hz > 0.0 && self.within_nyquist(f.name, f.hz))
+            .collect();
+        if tones.is_empty() {
+            return Err(invalid_input(format!(
+                "{} has no frequencies to place",
+                category.display_name()
+            )));
+        }
+
+        let voices: Vec<(Vec<f64>, f64)> = tones
+            .iter()
+            .enumerate()
+            .map(|(idx, f)| {
+                let pan = if tones.len() == 1 {
+                    0.0
+                } else {
+                    -1.0 + 2.0 * idx as f64 / (tones.len() - 1) as f64
+                };
+                println!("  {:>+5.2} pan  {:.2} Hz: {}", pan, f.hz, f.description);
+                (self.database_tone(f.hz, self.duration), pan)
+            })
+            .collect();
+
+        let samples = self.mix_panned(&voices);
+        let path = dir.join(format!("{}_panorama.wav", category.dir_name()));
+        self.save_stereo_wav(&path, &samples)
+    }
+
+    /// Generate a short clip of each category's first frequency
+    ///
+    /// Clips go into a `sampler` folder, or into one file with `--concat`.
+    /// Categories whose first entry is a zero-Hz placeholder are skipped.
+    pub fn generate_sampler(&self, each_secs: f64) -> Result<(), SpiritError> {
+        if each_secs <= 0.0 || each_secs.is_nan() {
+            return Err(invalid_input(format!(
+                "clip length must be positive (got {})",
+                each_secs
+            )));
+        }
+
+        let dir = self.output_dir.join("sampler");
+        fs::create_dir_all(&dir).ok();
+        println!("\n=== Generating Sampler: {} s per category ===", each_secs);
+
+        let mut titles = Vec::new();
+        let mut clips = Vec::new();
+        for category in Category::all() {
+            let Some(first) = category.frequencies().first().filter(|f| f.hz > 0.0) else {
+                continue;
+            };
+            println!(
+                "  {}: {:.2} Hz ({})",
+                category.display_name(),
+                first.hz,
+                first.name
+            );
+
+            let mut clip = self.database_tone(first.hz, each_secs);
+            self.apply_fade(&mut clip, 0.05);
+            if self.concat {
+                titles.push(category.display_name().to_string());
+                clips.push(clip);
+            } else {
+                let path = dir.join(format!(
+                    "{}_{}_{:.2}hz.wav",
+                    category.dir_name(),
+                    first.name,
+                    first.hz
+                ));
+                self.save_mono_wav(&path, &clip)?;
+            }
+        }
+
+        if self.concat {
+            self.save_sequence(&dir.join("sampler.wav"), "Sampler", &titles, &clips, false)?;
+        }
+        Ok(())
+    }
+
+    /// Generate one file previewing each category's first tone, with a cue per category
+    pub fn generate_audio_index(&self, each_secs: f64) -> Result<(), SpiritError> {
+        if each_secs <= 0.0 || each_secs.is_nan() {
+            return Err(invalid_input(format!(
+                "segment length must be positive (got {})",
+                each_secs
+            )));
+        }
+
+        fs::create_dir_all(&self.output_dir).ok();
+        println!(
+            "\n=== Generating Audio Index: {} s per category ===",
+            each_secs
+        );
+
+        let mut titles = Vec::new();
+        let mut segments = Vec::new();
+        for category in Category::all() {
+            let Some(first) = category.frequencies().iter().find(|f| f.hz > 0.0) else {
+                continue;
+            };
+            println!(
+                "  {}: {:.2} Hz ({})",
+                category.display_name(),
+                first.hz,
+                first.name
+            );
+
+            let mut segment = self.database_tone(first.hz, each_secs);
+            self.apply_fade(&mut segment, 0.05);
+            titles.push(category.display_name().to_string());
+            segments.push(segment);
+        }
+
+        let path = self.output_dir.join("audio_index.wav");
+        let cues = self.save_sequence(&path, "Audio Index", &titles, &segments, false)?;
+        wav::append_cue_points(&path, &cues)?;
+        println!("  Added {} cue markers", cues.len());
+        Ok(())
+    }
+
+    /// Generate every database frequency within `tolerance` Hz of `target`
+    ///
+    /// Matches from all categories go into one `near_<target>hz` folder, with
+    /// each file prefixed by its category so duplicates stay distinguishable.
+    pub fn generate_near(&self, target: f64, tolerance: f64) -> Result<(), SpiritError> {
+        if !target.is_finite() || target <= 0.0 {
+            return Err(invalid_frequency(format!(
+                "target frequency must be positive (got {})",
+                target
+            )));
+        }
+        if tolerance < 0.0 || tolerance.is_nan() {
+            return Err(invalid_input(format!(
+                "tolerance must not be negative (got {})",
+                tolerance
+            )));
+        }
+
+        let matches: Vec<(Category, &FrequencyInfo)> = Category::all()
+            .iter()
+            .flat_map(|&category| category.frequencies().iter().map(move |f| (category, f)))
+            .filter(|(_, f)| f.hz > 0.0 && (f.hz - target).abs() <= tolerance)
+            .collect();
+
+        println!(
+            "\n=== Generating {} Frequencies Within {} Hz of {} Hz ===",
+            matches.len(),
+            tolerance,
+            target
+        );
+        if matches.is_empty() {
+            return Ok(());
+        }
+
+        let dir = self.output_dir.join(format!("near_{}hz", target));
+        fs::create_dir_all(&dir).ok();
+        for (category, freq_info) in matches {
+            println!(
+                "  [{}] {:.2} Hz: {}",
+                category.display_name(),
+                freq_info.hz,
+                freq_info.name
+            );
+            self.generate_frequency_file(&dir, category.file_prefix(), freq_info, false)?;
+        }
+        Ok(())
+    }
+
+    /// Compare two categories by frequency, optionally rendering a set of them
+    ///
+    /// Frequencies within 0.01 Hz count as shared. Rendered files go into a
+    /// `diff_<a>_<b>_<operation>` folder, prefixed with their source category;
+    /// a shared frequency is rendered once, from the first category.
+    pub fn generate_category_diff(
+        &self,
+        first: Category,
+        second: Category,
+        operation: Option<SetOperation>,
+    ) -> Result<(), SpiritError> {
+        let (shared, only_a, only_b) = compare_categories(first, second);
+
+        println!(
+            "\n=== Comparing {} and {} ===",
+            first.display_name(),
+            second.display_name()
+        );
+        for (label, list) in [
+            ("Shared".to_string(), &shared),
+            (format!("Only in {}", first.dir_name()), &only_a),
+            (format!("Only in {}", second.dir_name()), &only_b),
+        ] {
+            let hz: Vec<String> = list.iter().map(|f| format!("{:.2}", f.hz)).collect();
+            let hz = if hz.is_empty() {
+                "none".to_string()
+            } else {
+                hz.join(", ")
+            };
+            println!("  {} ({}): {}", label, list.len(), hz);
+        }
+
+        let Some(operation) = operation else {
+            return Ok(());
+        };
+        let selected: Vec<(Category, &FrequencyInfo)> = match operation {
+            SetOperation::Intersection => shared.iter().map(|&f| (first, f)).collect(),
+            SetOperation::Union => shared
+                .iter()
+                .chain(&only_a)
+                .map(|&f| (first, f))
+                .chain(only_b.iter().map(|&f| (second, f)))
+                .collect(),
+        };
+
+        let dir = self.output_dir.join(
+            format!(
+                "diff_{}_{}_{:?}",
+                first.dir_name(),
+                second.dir_name(),
+                operation
+            )
+            .to_lowercase(),
+        );
+        fs::create_dir_all(&dir).ok();
+        println!(
+            "\n=== Generating {:?} ({} tones) ===",
+            operation,
+            selected.len()
+        );
+        for (category, freq_info) in selected {
+            self.generate_frequency_file(&dir, category.dir_name(), freq_info, false)?;
+        }
+        Ok(())
+    }
+
+    /// Generate a multi-carrier binaural beat file
+    pub fn generate_multi_binaural_file(
+        &self,
+        carriers: &[f64],
+        beat_freq: f64,
+    ) -> Result<(), SpiritError> {
+        if carriers.is_empty() {
+            return Err(invalid_input(
+                "at least one carrier is required".to_string(),
+            ));
+        }
+        if beat_freq <= 0.0 || !beat_freq.is_finite() {
+            return Err(invalid_frequency(format!(
+                "beat frequency must be positive (got {})",
+                beat_freq
+            )));
+        }
+        // The right channel sounds each carrier plus the beat
+        let nyquist = self.config.sample_rate as f64 / 2.0;
+        if let Some(carrier) = carriers
+            .iter()
+            .find(|c| **c <= 0.0 || !c.is_finite() || **c + beat_freq >= nyquist)
+        {
+            return Err(invalid_frequency(format!(
+                "carriers must be positive and, with the beat added, below Nyquist ({} Hz), got {} Hz",
+                nyquist, carrier
+            )));
+        }
+
+        fs::create_dir_all(&self.output_dir).ok();
+
+        let carrier_str: Vec<String> = carriers.iter().map(|f| format!("{:.0}", f)).collect();
+        println!(
+            "\n=== Generating Multi-Carrier Binaural: {} Hz, {} Hz beat ===",
+            carrier_str.join(", "),
+            beat_freq
+        );
+        println!("(Use headphones for binaural beats to work!)");
+
+        let samples = self.generate_multi_binaural(carriers, beat_freq, self.duration);
+        let filename = format!(
+            "multi_binaural_{}_{:.2}hz.wav",
+            carrier_str.join("_"),
+            beat_freq
+        );
+        self.save_stereo_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate the Om tone as a binaural beat, offset by `beat_freq` on the right
+    pub fn generate_om_binaural_file(&self, beat_freq: f64) -> Result<(), SpiritError> {
+        if !(beat_freq > 0.0 && beat_freq.is_finite()) {
+            return Err(invalid_frequency(format!(
+                "beat frequency must be positive (got {})",
+                beat_freq
+            )));
+        }
+
+        let dir = self.output_dir.join("binaural");
+        fs::create_dir_all(&dir).ok();
+        println!(
+            "\n=== Generating Om Binaural: {} Hz Om, {} Hz beat ===",
+            OM_HZ, beat_freq
+        );
+        println!("(Use headphones for binaural beats to work!)");
+
+        let left = self.om_wave(0.0, self.duration);
+        let right = self.om_wave(beat_freq, self.duration);
+        let frames: Vec<[f64; 2]> = left.into_iter().zip(right).map(|(l, r)| [l, r]).collect();
+
+        let filename = format!("om_binaural_{}hz_{:.2}hz.wav", OM_HZ, beat_freq);
+        self.save_stereo_wav(&dir.join(filename), &frames)
+    }
+
+    /// Generate several binaural beats layered on one base carrier
+    pub fn generate_multi_beat_file(&self, base: f64, beats: &[f64]) -> Result<(), SpiritError> {
+        if beats.is_empty() {
+            return Err(invalid_input("at least one beat is required".to_string()));
+        }
+        if let Some(beat) = beats.iter().find(|b| **b <= 0.0 || !b.is_finite()) {
+            return Err(invalid_frequency(format!(
+                "beat frequencies must be positive (got {})",
+                beat
+            )));
+        }
+
+        fs::create_dir_all(&self.output_dir).ok();
+
+        let beat_str: Vec<String> = beats.iter().map(|b| format!("{:.2}", b)).collect();
+        println!(
+            "\n=== Generating Multi-Beat Binaural: {} Hz base, {} Hz beats ===",
+            base,
+            beat_str.join(", ")
+        );
+        println!("(Use headphones for binaural beats to work!)");
+
+        let samples = self.generate_multi_beat(base, beats, self.duration);
+        let filename = format!("multi_beat_{:.0}hz_{}hz.wav", base, beat_str.join("_"));
+        self.save_stereo_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a sleep preset: a binaural beat descending from alpha into delta
+    ///
+    /// The beat glides from 10 Hz to 2 Hz across the whole file over a pink
+    /// noise bed that swells in during the first quarter and then holds.
+    /// Everything fades in over 10 seconds and out over the final fifth, so
+    /// the file ends in silence rather than stopping mid-tone.
+    pub fn generate_sleep_descent_file(
+        &self,
+        base: f64,
+        noise_level: f64,
+    ) -> Result<(), SpiritError> {
+        const START_BEAT: f64 = 10.0;
+        const END_BEAT: f64 = 2.0;
+        const TONE_LEVEL: f64 = 0.7;
+        const FADE_IN_SECS: f64 = 10.0;
+
+        if !(0.0..=1.0).contains(&noise_level) {
+            return Err(invalid_input(format!(
+                "noise level must be between 0 and 1 (got {})",
+                noise_level
+            )));
+        }
+
+        let dir = self.output_dir.join("binaural");
+        fs::create_dir_all(&dir).ok();
+        println!(
+            "\n=== Generating Sleep Descent: {} Hz base, {} Hz to {} Hz beat ===",
+            base, START_BEAT, END_BEAT
+        );
+        println!("(Use headphones for binaural beats to work!)");
+
+        let mut frames = self.generate_binaural_ramp(base, START_BEAT, END_BEAT, self.duration);
+        let noise = self.generate_pink_noise(self.duration);
+
+        let len = frames.len();
+        let rate = self.config.sample_rate as f64;
+        let swell = (len / 4).max(1);
+        let fade_in = ((rate * FADE_IN_SECS) as usize).min(len / 2).max(1);
+        let fade_out = (len / 5).max(1);
+        for (i, (frame, n)) in frames.iter_mut().zip(noise).enumerate() {
+            let bed = noise_level * self.fade_window.ramp((i as f64 / swell as f64).min(1.0)) * n;
+            let gain = if i < fade_in {
+                self.fade_window.ramp(i as f64 / fade_in as f64)
+            } else if i >= len - fade_out {
+                self.fade_window.ramp((len - i) as f64 / fade_out as f64)
+            } else {
+                1.0
+            };
+            frame[0] = gain * (TONE_LEVEL * frame[0] + bed);
+            frame[1] = gain * (TONE_LEVEL * frame[1] + bed);
+        }
+
+        let filename = format!(
+            "sleep_descent_{:.0}hz_{}to{}hz.wav",
+            base, START_BEAT, END_BEAT
+        );
+        self.save_stereo_wav(&dir.join(filename), &frames)
+    }
+
+    /// Generate a beat sweep for finding where a listener stops hearing the beat
+    ///
+    /// Cue markers label the beat rate at the start, every 5 Hz, and the end,
+    /// so a player's marker list shows where along the sweep the beat faded.
+    pub fn generate_binaural_calibration(
+        &self,
+        base: f64,
+        start_beat: f64,
+        end_beat: f64,
+    ) -> Result<(), SpiritError> {
+        const MARKER_STEP_HZ: f64 = 5.0;
+
+        if !(start_beat > 0.0 && end_beat > start_beat && end_beat.is_finite()) {
+            return Err(invalid_frequency(format!(
+                "beat sweep must rise from a positive start (got {} to {} Hz)",
+                start_beat, end_beat
+            )));
+        }
+        fs::create_dir_all(&self.output_dir).ok();
+
+        println!(
+            "\n=== Generating Binaural Calibration: {} Hz base, {} to {} Hz beat ===",
+            base, start_beat, end_beat
+        );
+        let frames = self.generate_binaural_ramp(base, start_beat, end_beat, self.duration);
+
+        let first_step = (start_beat / MARKER_STEP_HZ).floor() as u32 + 1;
+        let steps = (first_step..)
+            .map(|n| n as f64 * MARKER_STEP_HZ)
+            .take_while(|&beat| beat < end_beat);
+        let last_frame = frames.len().saturating_sub(1) as f64;
+        let cues: Vec<(u32, String)> = std::iter::once(start_beat)
+            .chain(steps)
+            .chain(std::iter::once(end_beat))
+            .map(|beat| {
+                let progress = (beat - start_beat) / (end_beat - start_beat);
+                let frame = (progress * last_frame).round() as u32;
+                let secs = frame as f64 / self.config.sample_rate as f64;
+                println!("  {:>7.2} s: {} Hz beat", secs, beat);
+                (frame, format!("Beat {} Hz", beat))
+            })
+            .collect();
+
+        let filename = format!(
+            "binaural_calibrate_{:.0}hz_{}_to_{}hz.wav",
+            base, start_beat, end_beat
+        );
+        let path = self.output_dir.join(filename);
+        self.save_stereo_wav(&path, &frames)?;
+        wav::append_cue_points(&path, &cues)?;
+        println!("  Added {} cue markers", cues.len());
+        Ok(())
+    }
+
+    /// Generate each brainwave state's midpoint frequency as a standalone tone
+    ///
+    /// Sine mode still uses an isochronic tone for sub-audible bands, and those
+    /// files are named `_isochronic` to match what they contain.
+    pub fn generate_brainwave_tones(&self, mode: &GenerationMode) -> Result<(), SpiritError> {
+        let dir = self.output_dir.join("brainwaves");
+        fs::create_dir_all(&dir).ok();
+
+        println!("\n=== Generating Brainwave State Tones ({:?}) ===", mode);
+
+        for state in BRAINWAVE_STATES {
+            let midpoint = (state.low_hz + state.high_hz) / 2.0;
+            println!(
+                "  {} ({} Hz): {}",
+                state.name.to_uppercase(),
+                midpoint,
+                state.description
+            );
+
+            let rendered = match mode {
+                GenerationMode::Sine if midpoint < 20.0 => GenerationMode::Isochronic,
+                other => *other,
+            };
+            let path = dir.join(format!(
+                "brainwave_{}_{:.2}hz_{}.wav",
+                state.name,
+                midpoint,
+                format!("{:?}", rendered).to_lowercase()
+            ));
+            match rendered {
+                GenerationMode::Sine => {
+                    self.save_mono_wav(&path, &self.generate_sine_wave(midpoint, self.duration))?
+                }
+                GenerationMode::Isochronic => {
+                    let samples = self.generate_isochronic_tone(200.0, midpoint, self.duration);
+                    self.save_mono_wav(&path, &samples)?
+                }
+                GenerationMode::Binaural => {
+                    let samples = self.generate_binaural_beat(200.0, midpoint, self.duration);
+                    self.save_stereo_wav(&path, &samples)?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generate Schumann resonance (7.83 Hz)
+    pub fn generate_schumann(&self) -> Result<(), SpiritError> {
+        let dir = self.output_dir.join("schumann");
+        fs::create_dir_all(&dir).ok();
+
+        println!("\n=== Generating Schumann Resonance (7.83 Hz) ===");
+
+        println!("  Isochronic tone (works without headphones)");
+        let samples = self.generate_isochronic_tone(200.0, 7.83, self.duration);
+        self.save_mono_wav(&dir.join("schumann_7.83hz_isochronic.wav"), &samples)?;
+
+        println!("  Binaural beat (requires headphones)");
+        let samples = self.generate_binaural_beat(200.0, 7.83, self.duration);
+        self.save_stereo_wav(&dir.join("schumann_7.83hz_binaural.wav"), &samples)?;
+
+        Ok(())
+    }
+
+    /// Generate chakra meditation sequence
+    pub fn generate_chakra_meditation(&self) -> Result<(), SpiritError> {
+        let dir = self.output_dir.join("chakras");
+        fs::create_dir_all(&dir).ok();
+
+        println!("\n=== Generating Chakra Meditation Sequence ===");
+
+        let mut full_sequence: Vec<f64> = Vec::new();
+
+        for freq_info in Category::Chakras.frequencies() {
+            println!(
+                "  {} ({} Hz): {}",
+                freq_info.name, freq_info.hz, freq_info.description
+            );
+
+            let mut samples = self.generate_sine_wave(freq_info.hz, self.duration);
+            self.apply_fade(&mut samples, 2.0);
+
+            let path = dir.join(format!(
+                "chakra_{}_{:.0}hz.wav",
+                freq_info.name, freq_info.hz
+            ));
+            self.save_mono_wav(&path, &samples)?;
+
+            full_sequence.extend_from_slice(&samples);
+        }
+
+        println!("  Full meditation sequence...");
+        self.save_mono_wav(&dir.join("chakra_full_meditation.wav"), &full_sequence)?;
+
+        Ok(())
+    }
+
+    /// Generate 432 Hz vs 440 Hz tuning comparison
+    ///
+    /// With `simultaneous`, the comparison file plays both tones together so
+    /// their 8 Hz interference beat is audible, instead of alternating them.
+    pub fn generate_tuning_comparison(&self, simultaneous: bool) -> Result<(), SpiritError> {
+        let dir = self.output_dir.join("tuning");
+        fs::create_dir_all(&dir).ok();
+
+        println!("\n=== Generating 432 Hz vs 440 Hz Comparison ===");
+
+        let samples_432 = self.generate_sine_wave(432.0, self.duration);
+        let samples_440 = self.generate_sine_wave(440.0, self.duration);
+
+        self.save_mono_wav(&dir.join("tuning_432hz_natural.wav"), &samples_432)?;
+        self.save_mono_wav(&dir.join("tuning_440hz_standard.wav"), &samples_440)?;
+
+        if simultaneous {
+            println!("  Simultaneous (8 Hz beat)...");
+            let together = self.generate_layered_frequencies(&[432.0, 440.0], self.duration);
+            return self.save_mono_wav(&dir.join("tuning_432_440_simultaneous.wav"), &together);
+        }
+
+        println!("  A-B comparison (alternating)...");
+        let segment_duration = 5.0;
+        let num_segments = (self.duration / (segment_duration * 2.0)) as usize;
+        let mut comparison: Vec<f64> = Vec::new();
+
+        for _ in 0..num_segments.max(1) {
+            comparison.extend(self.generate_sine_wave(432.0, segment_duration));
+            comparison.extend(self.generate_sine_wave(440.0, segment_duration));
+        }
+
+        self.save_mono_wav(&dir.join("tuning_432_440_comparison.wav"), &comparison)
+    }
+
+    /// Generate Om tone
+    pub fn generate_om(&self) -> Result<(), SpiritError> {
+        fs::create_dir_all(&self.output_dir).ok();
+
+        println!("\n=== Generating Om Tone ({} Hz with harmonics) ===", OM_HZ);
+        let samples = self.generate_om_tone(self.duration);
+        self.save_mono_wav(
+            &self.output_dir.join(format!("om_{}hz.wav", OM_HZ)),
+            &samples,
+        )
+    }
+
+    /// Generate noise backgrounds
+    pub fn generate_noise_set(&self) -> Result<(), SpiritError> {
+        let dir = self.output_dir.join("noise");
+        fs::create_dir_all(&dir).ok();
+
+        println!("\n=== Generating Noise Backgrounds ===");
+
+        println!("  White noise (all frequencies equal)");
+        self.save_mono_wav(
+            &dir.join("white_noise.wav"),
+            &self.generate_white_noise(self.duration),
+        )?;
+
+        println!("  Pink noise (1/f, nature-like)");
+        self.save_mono_wav(
+            &dir.join("pink_noise.wav"),
+            &self.generate_pink_noise(self.duration),
+        )?;
+
+        println!("  Brown noise (1/f², deep rumble)");
+        self.save_mono_wav(
+            &dir.join("brown_noise.wav"),
+            &self.generate_brown_noise(self.duration),
+        )
+    }
+
+    /// Validate sweep endpoints, clamping any that exceed Nyquist
+    pub fn validate_sweep_endpoints(
+        &self,
+        start: f64,
+        end: f64,
+    ) -> Result<(f64, f64), SpiritError> {
+        for (label, freq) in [("start", start), ("end", end)] {
+            if !freq.is_finite() || freq <= 0.0 {
+                return Err(invalid_frequency(format!(
+                    "sweep {} frequency must be greater than 0 Hz (got {})",
+                    label, freq
+                )));
+            }
+        }
+
+        let nyquist = self.config.sample_rate as f64 / 2.0;
+        let clamp = |label: &str, freq: f64| {
+            if freq > nyquist {
+                eprintln!(
+                    "  Warning: sweep {} frequency {} Hz exceeds Nyquist ({} Hz), clamping",
+                    label, freq, nyquist
+                );
+                nyquist
+            } else {
+                freq
+            }
+        };
+
+        Ok((clamp("start", start), clamp("end", end)))
+    }
+
+    /// Generate periodic noise bursts
+    pub fn generate_noise_burst_file(
+        &self,
+        color: NoiseColor,
+        on_secs: f64,
+        off_secs: f64,
+    ) -> Result<(), SpiritError> {
+        if on_secs <= 0.0 || off_secs < 0.0 {
+            return Err(invalid_input(format!(
+                "burst on time must be positive and off time non-negative (got {} / {})",
+                on_secs, off_secs
+            )));
+        }
+
+        let dir = self.output_dir.join("noise");
+        fs::create_dir_all(&dir).ok();
+
+        println!(
+            "\n=== Generating {:?} Noise Bursts: {} s on / {} s off ===",
+            color, on_secs, off_secs
+        );
+
+        let mut samples = self.generate_noise(color, self.duration);
+        self.apply_gate(&mut samples, on_secs, off_secs);
+
+        let filename = format!(
+            "noise_burst_{}_{}s_on_{}s_off.wav",
+            color.name(),
+            on_secs,
+            off_secs
+        );
+        self.save_mono_wav(&dir.join(filename), &samples)
+    }
+
+    /// Generate noise pulsed by an isochronic envelope at `beat_freq`
+    pub fn generate_noise_entrainment_file(
+        &self,
+        color: NoiseColor,
+        beat_freq: f64,
+    ) -> Result<(), SpiritError> {
+        if !(beat_freq > 0.0 && beat_freq.is_finite()) {
+            return Err(invalid_frequency(format!(
+                "beat frequency must be positive (got {})",
+                beat_freq
+            )));
+        }
+
+        let dir = self.output_dir.join("noise");
+        fs::create_dir_all(&dir).ok();
+        println!(
+            "\n=== Generating {:?} Noise Entrainment: {} Hz ===",
+            color, beat_freq
+        );
+
+        let rate = self.config.sample_rate as f64;
+        let mut samples = self.generate_noise(color, self.duration);
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let phase = (beat_freq * i as f64 / rate).rem_euclid(1.0);
+            *sample *= isochronic_envelope(phase, self.duty);
+        }
+        self.apply_fade(&mut samples, 0.05);
+
+        let filename = format!("noise_entrainment_{}_{}hz.wav", color.name(), beat_freq);
+        self.save_mono_wav(&dir.join(filename), &samples)
+    }
+
+    /// Generate a fractional Brownian noise file
+    pub fn generate_fractional_noise_file(&self, hurst: f64) -> Result<(), SpiritError> {
+        if !(hurst > 0.0 && hurst < 1.0) {
+            return Err(invalid_input(format!(
+                "Hurst exponent must be between 0 and 1 (got {})",
+                hurst
+            )));
+        }
+
+        let dir = self.output_dir.join("noise");
+        fs::create_dir_all(&dir).ok();
+        println!(
+            "\n=== Generating Fractional Noise: H = {} (1/f^{:.2}) ===",
+            hurst,
+            2.0 * hurst + 1.0
+        );
+
+        let mut samples = self.generate_fractional_noise(hurst, self.duration);
+        self.apply_fade(&mut samples, 0.05);
+        let filename = format!("fractional_noise_h{}.wav", hurst);
+        self.save_mono_wav(&dir.join(filename), &samples)
+    }
+
+    /// Generate pink noise at a calibrated RMS level for room measurement
+    ///
+    /// The level is RMS relative to full scale, set after the edge fades so
+    /// the file as written measures `dbfs`.
+    pub fn generate_room_pink_file(&self, dbfs: f64) -> Result<(), SpiritError> {
+        if !(-60.0..=-6.0).contains(&dbfs) {
+            return Err(invalid_input(format!(
+                "RMS level must be between -60 and -6 dBFS (got {})",
+                dbfs
+            )));
+        }
+
+        let dir = self.output_dir.join("noise");
+        fs::create_dir_all(&dir).ok();
+        println!("\n=== Generating Room Pink Noise: {} dBFS RMS ===", dbfs);
+
+        let mut samples = self.generate_pink_noise(self.duration);
+        self.apply_fade(&mut samples, 0.05);
+        effects::normalize_rms(&mut samples, 10f64.powf(dbfs / 20.0));
+
+        let peak = samples.iter().fold(0.0f64, |acc, s| acc.max(s.abs()));
+        println!(
+            "  RMS {:.2} dBFS, peak {:.2} dBFS",
+            20.0 * analysis::rms(&samples).log10(),
+            20.0 * peak.log10()
+        );
+        if peak > 1.0 {
+            eprintln!("  Warning: peaks exceed full scale and will clip");
+        }
+
+        let filename = format!("room_pink_{}dbfs.wav", dbfs);
+        self.save_mono_wav(&dir.join(filename), &samples)
+    }
+
+    /// Convert an existing WAV to the configured sample rate and bit depth
+    ///
+    /// When the output path ends in `.wav` it names the converted file;
+    /// otherwise the file is written into it as `<stem>_<rate>hz.wav`.
+    pub fn convert_file(&self, input: &Path, method: Resampler) -> Result<(), SpiritError> {
+        let (channels, rate, samples) = wav::read_wav(input)?;
+        let target_rate = self.config.sample_rate;
+        println!(
+            "\n=== Converting {}: {} Hz -> {} Hz, {}-bit ===",
+            input.display(),
+            rate,
+            target_rate,
+            self.config.bit_depth
+        );
+
+        let resampled: Vec<Vec<f64>> = deinterleave(&samples, channels)
+            .iter()
+            .map(|channel| resample::resample(channel, rate, target_rate, method))
+            .collect();
+
+        let path = if self
+            .output_dir
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+        {
+            if let Some(parent) = self.output_dir.parent() {
+                fs::create_dir_all(parent).ok();
+            }
+            self.output_dir.clone()
+        } else {
+            fs::create_dir_all(&self.output_dir).ok();
+            let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+            self.output_dir
+                .join(format!("{}_{}hz.wav", stem, target_rate))
+        };
+        self.write_wav(&path, channels, &interleave(&resampled))
+    }
+
+    /// Generate a measurement signal for checking a playback or processing chain
+    ///
+    /// Impulses and steps are written without fades so their edges stay exact.
+    pub fn generate_test_signal_file(
+        &self,
+        signal: TestSignal,
+        start: f64,
+        end: f64,
+    ) -> Result<(), SpiritError> {
+        fs::create_dir_all(&self.output_dir).ok();
+        let num_samples = self.sample_count(self.duration);
+        let midpoint = num_samples / 2;
+
+        let (samples, filename) = match signal {
+            TestSignal::Impulse => {
+                println!(
+                    "\n=== Generating Test Signal: Impulse at sample {} ===",
+                    midpoint
+                );
+                let mut samples = vec![0.0; num_samples];
+                if let Some(sample) = samples.get_mut(midpoint) {
+                    *sample = 1.0;
+                }
+                (samples, "test_impulse.wav".to_string())
+            }
+            TestSignal::Step => {
+                println!(
+                    "\n=== Generating Test Signal: Step at sample {} ===",
+                    midpoint
+                );
+                let samples = (0..num_samples)
+                    .map(|i| if i < midpoint { 0.0 } else { 1.0 })
+                    .collect();
+                (samples, "test_step.wav".to_string())
+            }
+            TestSignal::Chirp => {
+                let (start, end) = self.validate_sweep_endpoints(start, end)?;
+                println!(
+                    "\n=== Generating Test Signal: Linear Chirp {} Hz to {} Hz ===",
+                    start, end
+                );
+                let samples = self.generate_glide(start, end, self.duration);
+                (
+                    samples,
+                    format!("test_chirp_{:.0}hz_to_{:.0}hz.wav", start, end),
+                )
+            }
+        };
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a frequency comb: log-spaced tones sounding together
+    ///
+    /// Tones always start at seeded random phases; aligned phases would stack
+    /// into one tall peak per period and force the whole comb quieter.
+    pub fn generate_comb_file(
+        &self,
+        start: f64,
+        end: f64,
+        count: usize,
+    ) -> Result<(), SpiritError> {
+        let nyquist = self.config.sample_rate as f64 / 2.0;
+        if !(start > 0.0 && end > start && end < nyquist) {
+            return Err(invalid_frequency(format!(
+                "comb must rise from a positive start to below Nyquist ({} Hz), got {} to {} Hz",
+                nyquist, start, end
+            )));
+        }
+        if count == 0 {
+            return Err(invalid_input("comb needs at least one tone".to_string()));
+        }
+
+        fs::create_dir_all(&self.output_dir).ok();
+        println!(
+            "\n=== Generating Frequency Comb: {} Tones, {} Hz to {} Hz ===",
+            count, start, end
+        );
+
+        let frequencies = Self::log_spaced(start, end, count);
+        let freq_str: Vec<String> = frequencies.iter().map(|f| format!("{:.1}", f)).collect();
+        println!("  Tones: {} Hz", freq_str.join(", "));
+
+        let partials: Vec<(f64, f64)> = frequencies.iter().map(|&f| (f, 1.0)).collect();
+        let samples = self.layer_shaped(&partials, true, Some(0.05));
+
+        let filename = format!("comb_{:.0}hz_to_{:.0}hz_{}.wav", start, end, count);
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Play each digit of `sequence` as a tone at `base + digit * step` Hz
+    ///
+    /// Anything that is not a digit (decimal points, spaces, newlines) is
+    /// skipped, so `3.14159` and `3 1 4 1 5 9` sound the same. Each digit is
+    /// one `note_secs` segment of the file and one cue sheet track.
+    pub fn generate_sonify_file(
+        &self,
+        sequence: &str,
+        base: f64,
+        step: f64,
+        note_secs: f64,
+    ) -> Result<(), SpiritError> {
+        let digits: Vec<u32> = sequence.chars().filter_map(|c| c.to_digit(10)).collect();
+        if digits.is_empty() {
+            return Err(invalid_input("sonify needs at least one digit".to_string()));
+        }
+        let nyquist = self.config.sample_rate as f64 / 2.0;
+        let (low, high) = (base.min(base + 9.0 * step), base.max(base + 9.0 * step));
+        if !(low > 0.0 && high < nyquist) {
+            return Err(invalid_frequency(format!(
+                "digit tones must stay between 0 and Nyquist ({} Hz), got {} to {} Hz",
+                nyquist, low, high
+            )));
+        }
+        if !(note_secs > 0.0 && note_secs.is_finite()) {
+            return Err(invalid_input(format!(
+                "note length must be positive, got {} s",
+                note_secs
+            )));
+        }
+
+        fs::create_dir_all(&self.output_dir).ok();
+        println!(
+            "\n=== Sonifying {} Digits: {} Hz + digit x {} Hz ===",
+            digits.len(),
+            base,
+            step
+        );
+
+        let (titles, segments): (Vec<String>, Vec<Vec<f64>>) = digits
+            .iter()
+            .map(|&digit| {
+                let hz = base + digit as f64 * step;
+                let mut note = self.generate_sine_wave(hz, note_secs);
+                self.apply_fade(&mut note, (note_secs / 10.0).min(0.02));
+                (format!("Digit {} ({:.0} Hz)", digit, hz), note)
+            })
+            .unzip();
+
+        let preview: String = digits.iter().take(16).map(|d| d.to_string()).collect();
+        println!(
+            "  Sequence: {}{}",
+            preview,
+            if digits.len() > 16 { "..." } else { "" }
+        );
+
+        let filename = format!("sonify_{}.wav", preview);
+        let path = self.output_dir.join(filename);
+        self.save_sequence(&path, "Sonified Sequence", &titles, &segments, false)?;
+        Ok(())
+    }
+
+    /// Generate a frequency sweep file
+    pub fn generate_frequency_sweep_file(&self, start: f64, end: f64) -> Result<(), SpiritError> {
+        let (start, end) = self.validate_sweep_endpoints(start, end)?;
+        fs::create_dir_all(&self.output_dir).ok();
+
+        println!(
+            "\n=== Generating Frequency Sweep: {} Hz to {} Hz ===",
+            start, end
+        );
+        let samples = self.generate_frequency_sweep(start, end, self.duration);
+        let filename = format!("sweep_{:.0}hz_to_{:.0}hz.wav", start, end);
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a linear glide file between two frequencies
+    pub fn generate_glide_file(&self, start: f64, end: f64) -> Result<(), SpiritError> {
+        let (start, end) = self.validate_sweep_endpoints(start, end)?;
+        fs::create_dir_all(&self.output_dir).ok();
+
+        println!("\n=== Generating Glide: {} Hz to {} Hz ===", start, end);
+        let samples = self.generate_glide(start, end, self.duration);
+        let filename = format!("glide_{:.0}hz_to_{:.0}hz.wav", start, end);
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a drone file from multiple frequencies
+    ///
+    /// With `swell`, the output is shaped by a `(period_secs, depth)` amplitude swell.
+    pub fn generate_drone_file(
+        &self,
+        frequencies: &[f64],
+        swell: Option<(f64, f64)>,
+        filter_sweep: Option<(f64, f64, f64)>,
+    ) -> Result<(), SpiritError> {
+        if frequencies.is_empty() {
+            return Err(invalid_input(
+                "drone needs at least one frequency".to_string(),
+            ));
+        }
+        if let Some((period, _)) = swell {
+            if period <= 0.0 || period.is_nan() {
+                return Err(invalid_input(format!(
+                    "swell period must be positive (got {})",
+                    period
+                )));
+            }
+        }
+        if let Some((min, max, rate)) = filter_sweep {
+            if !(min > 0.0 && max >= min && rate > 0.0) {
+                return Err(invalid_input(format!(
+                    "filter sweep needs 0 < min <= max and a positive rate (got {}-{} Hz at {} Hz)",
+                    min, max, rate
+                )));
+            }
+        }
+
+        fs::create_dir_all(&self.output_dir).ok();
+
+        let freq_str: Vec<String> = frequencies.iter().map(|f| format!("{:.0}", f)).collect();
+        println!("\n=== Generating Drone: {} Hz ===", freq_str.join(", "));
+
+        let mut samples = self.generate_drone(frequencies, self.duration);
+        if let Some((period, depth)) = swell {
+            println!("  Swell: {} s period, depth {}", period, depth);
+            self.apply_swell(&mut samples, period, depth);
+        }
+        if let Some((min, max, rate)) = filter_sweep {
+            println!("  Filter sweep: {}-{} Hz at {} Hz", min, max, rate);
+            effects::apply_filter_sweep(&mut samples, self.config.sample_rate, min, max, rate);
+        }
+        let filename = format!("drone_{}.wav", freq_str.join("_"));
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a drone from a root and its octaves, each quieter than the last
+    pub fn generate_octave_drone_file(&self, root: f64, octaves: u32) -> Result<(), SpiritError> {
+        const OCTAVE_WEIGHT: f64 = 0.7;
+
+        if root <= 0.0 || !root.is_finite() {
+            return Err(invalid_frequency(format!(
+                "root frequency must be greater than 0 Hz (got {})",
+                root
+            )));
+        }
+
+        let nyquist = self.config.sample_rate as f64 / 2.0;
+        let stack = octave_stack(root, octaves, nyquist);
+        if stack.len() < octaves as usize {
+            eprintln!(
+                "  Warning: dropped {} octave(s) above Nyquist ({} Hz)",
+                octaves as usize - stack.len(),
+                nyquist
+            );
+        }
+        if stack.is_empty() {
+            return Err(invalid_frequency(format!(
+                "root {} Hz is above Nyquist ({} Hz)",
+                root, nyquist
+            )));
+        }
+
+        fs::create_dir_all(&self.output_dir).ok();
+
+        let freq_str: Vec<String> = stack.iter().map(|f| format!("{:.0}", f)).collect();
+        println!(
+            "\n=== Generating Octave Drone: {} Hz ===",
+            freq_str.join(", ")
+        );
+
+        let voices: Vec<(f64, f64)> = stack
+            .iter()
+            .enumerate()
+            .map(|(idx, &freq)| (freq, OCTAVE_WEIGHT.powi(idx as i32)))
+            .collect();
+        let samples = self.generate_drone_weighted(&voices, self.duration);
+        let filename = format!("octave_drone_{:.0}hz_x{}.wav", root, stack.len());
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a layered file, optionally splitting tones across stereo channels
+    pub fn generate_layered_file(
+        &self,
+        frequencies: &[f64],
+        layout: ChannelLayout,
+    ) -> Result<(), SpiritError> {
+        if frequencies.is_empty() {
+            return Err(invalid_input(
+                "layer needs at least one frequency".to_string(),
+            ));
+        }
+        fs::create_dir_all(&self.output_dir).ok();
+
+        let freq_str: Vec<String> = frequencies.iter().map(|f| format!("{:.0}", f)).collect();
+        println!(
+            "\n=== Generating Layered Frequencies: {} Hz ===",
+            freq_str.join(", ")
+        );
+
+        match layout {
+            ChannelLayout::Mono => {
+                let partials: Vec<(f64, f64)> = frequencies.iter().map(|&f| (f, 1.0)).collect();
+                let samples = self.layer_shaped(&partials, self.random_phase, None);
+                let filename = format!("layered_{}.wav", freq_str.join("_"));
+                self.save_mono_wav(&self.output_dir.join(filename), &samples)
+            }
+            ChannelLayout::Stereo => {
+                let samples = self.generate_split_layers(frequencies, self.duration);
+                let filename = format!("layered_{}_split.wav", freq_str.join("_"));
+                self.save_stereo_wav(&self.output_dir.join(filename), &samples)
+            }
+            _ => {
+                let channels =
+                    self.generate_channel_layers(frequencies, layout.channels(), self.duration);
+                let name = format!("{:?}", layout).to_lowercase();
+                let filename = format!("layered_{}_{}.wav", freq_str.join("_"), name);
+                self.write_wav(
+                    &self.output_dir.join(filename),
+                    layout.channels(),
+                    &interleave(&channels),
+                )
+            }
+        }
+    }
+
+    /// Generate a fundamental layered with its 1/n harmonic series
+    pub fn generate_harmonics_file(
+        &self,
+        fundamental: f64,
+        count: usize,
+        inharmonicity: f64,
+    ) -> Result<(), SpiritError> {
+        if !(inharmonicity >= 0.0 && inharmonicity.is_finite()) {
+            return Err(invalid_input(format!(
+                "inharmonicity must be zero or positive (got {})",
+                inharmonicity
+            )));
+        }
+        fs::create_dir_all(&self.output_dir).ok();
+
+        println!(
+            "\n=== Generating Harmonic Stack: {} Hz + {} harmonics ===",
+            fundamental, count
+        );
+        if inharmonicity > 0.0 {
+            println!("  Inharmonicity: B = {}", inharmonicity);
+        }
+
+        let partials = self.harmonic_series(fundamental, count, inharmonicity);
+        if partials.len() < count + 1 {
+            eprintln!(
+                "  Warning: {} harmonic(s) above Nyquist ({} Hz) skipped",
+                count + 1 - partials.len(),
+                self.config.sample_rate / 2
+            );
+        }
+        if partials.is_empty() {
+            return Err(invalid_frequency(format!(
+                "fundamental {} Hz is above Nyquist",
+                fundamental
+            )));
+        }
+        for (n, &(freq, weight)) in partials.iter().enumerate() {
+            println!("  H{}: {:.2} Hz (amplitude {:.3})", n + 1, freq, weight);
+        }
+
+        let samples = self.generate_layered_frequencies_weighted(&partials, self.duration);
+        let filename = if inharmonicity > 0.0 {
+            format!(
+                "harmonics_{:.2}hz_x{}_b{}.wav",
+                fundamental,
+                partials.len(),
+                inharmonicity
+            )
+        } else {
+            format!("harmonics_{:.2}hz_x{}.wav", fundamental, partials.len())
+        };
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a wavetable tone from a CSV or WAV single-cycle table
+    pub fn generate_wavetable_file(
+        &self,
+        table_path: &Path,
+        frequency: f64,
+    ) -> Result<(), SpiritError> {
+        let table = load_wavetable(table_path)?;
+        fs::create_dir_all(&self.output_dir).ok();
+
+        println!(
+            "\n=== Generating Wavetable: {} Hz from {} ({} points) ===",
+            frequency,
+            table_path.display(),
+            table.len()
+        );
+
+        let samples = self.generate_wavetable(&table, frequency, self.duration);
+        let stem = table_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "table".to_string());
+        let filename = format!("wavetable_{}_{:.2}hz.wav", stem, frequency);
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a two-tone intermodulation demo and print the expected products
+    pub fn generate_intermod_file(&self, f1: f64, f2: f64) -> Result<(), SpiritError> {
+        fs::create_dir_all(&self.output_dir).ok();
+
+        println!(
+            "\n=== Generating Intermodulation Pair: {} Hz + {} Hz ===",
+            f1, f2
+        );
+        println!("  Difference tone (f2 - f1):   {:.2} Hz", (f2 - f1).abs());
+        println!("  Sum tone (f1 + f2):          {:.2} Hz", f1 + f2);
+        println!(
+            "  Cubic difference (2f1 - f2): {:.2} Hz",
+            (2.0 * f1 - f2).abs()
+        );
+
+        let samples = self.generate_layered_frequencies(&[f1, f2], self.duration);
+        let filename = format!("intermod_{:.0}hz_{:.0}hz.wav", f1, f2);
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a chord of just-intonation ratios above a root frequency
+    pub fn generate_intervals_file(&self, root: f64, ratios: &[f64]) -> Result<(), SpiritError> {
+        if let Some(bad) = ratios.iter().find(|&&r| r <= 0.0 || !r.is_finite()) {
+            return Err(invalid_input(format!(
+                "interval ratios must be positive (got {})",
+                bad
+            )));
+        }
+
+        fs::create_dir_all(&self.output_dir).ok();
+
+        println!("\n=== Generating Intervals above {} Hz ===", root);
+        let frequencies = interval_frequencies(root, ratios);
+        for (ratio, freq) in ratios.iter().zip(&frequencies) {
+            println!("  x{:<6} {:.2} Hz", ratio, freq);
+        }
+
+        let samples = self.generate_layered_frequencies(&frequencies, self.duration);
+        let ratio_str: Vec<String> = ratios.iter().map(|r| format!("{}", r)).collect();
+        let filename = format!("intervals_{:.0}hz_{}.wav", root, ratio_str.join("_"));
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a tone layered with the pitch a cycle of twelve pure fifths lands on
+    ///
+    /// Stacking fifths and folding back down seven octaves overshoots the
+    /// start by the Pythagorean comma, so the pair beats audibly.
+    pub fn generate_comma_file(&self, frequency: f64) -> Result<(), SpiritError> {
+        if !frequency.is_finite() || frequency <= 0.0 {
+            return Err(invalid_frequency(format!(
+                "frequency must be positive (got {})",
+                frequency
+            )));
+        }
+        fs::create_dir_all(&self.output_dir).ok();
+
+        let companion = frequency * PYTHAGOREAN_COMMA;
+        println!("\n=== Generating Pythagorean Comma: {} Hz ===", frequency);
+        println!("  Twelve fifths, down seven octaves: {:.2} Hz", companion);
+        println!(
+            "  Comma: x{:.5} ({:.2} cents, {:.2} Hz beat)",
+            PYTHAGOREAN_COMMA,
+            1200.0 * PYTHAGOREAN_COMMA.log2(),
+            companion - frequency
+        );
+
+        let samples = self.generate_layered_frequencies(&[frequency, companion], self.duration);
+        let filename = format!("comma_{:.0}hz.wav", frequency);
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate an isochronic pulse locked to a tempo
+    ///
+    /// The pulse rate is `bpm / 60 * subdivision` Hz, so 60 BPM pulses once
+    /// per second and a subdivision of 2 gives eighth notes.
+    pub fn generate_pulse_file(
+        &self,
+        bpm: f64,
+        subdivision: u32,
+        carrier: f64,
+    ) -> Result<(), SpiritError> {
+        if !(bpm > 0.0 && bpm.is_finite()) || subdivision == 0 {
+            return Err(invalid_input(format!(
+                "tempo and subdivision must be positive (got {} BPM x{})",
+                bpm, subdivision
+            )));
+        }
+        fs::create_dir_all(&self.output_dir).ok();
+
+        let pulse_hz = bpm / 60.0 * subdivision as f64;
+        println!(
+            "\n=== Generating Pulse: {} BPM x{} ({:.3} Hz on {} Hz) ===",
+            bpm, subdivision, pulse_hz, carrier
+        );
+        let samples = self.generate_isochronic_tone(carrier, pulse_hz, self.duration);
+        let filename = format!("pulse_{}bpm_x{}_{:.0}hz.wav", bpm, subdivision, carrier);
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a tone that morphs from one waveform to another
+    pub fn generate_morph_file(
+        &self,
+        frequency: f64,
+        from: Waveform,
+        to: Waveform,
+    ) -> Result<(), SpiritError> {
+        if !frequency.is_finite() || frequency <= 0.0 {
+            return Err(invalid_frequency(format!(
+                "frequency must be positive (got {})",
+                frequency
+            )));
+        }
+        fs::create_dir_all(&self.output_dir).ok();
+
+        println!(
+            "\n=== Generating Morph: {} Hz, {:?} to {:?} ===",
+            frequency, from, to
+        );
+        let mut samples = self.generate_morph(frequency, from, to, self.duration);
+        self.apply_fade(&mut samples, 0.05);
+        let filename =
+            format!("morph_{:.0}hz_{:?}_to_{:?}.wav", frequency, from, to).to_lowercase();
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a reference tone mixed with a copy detuned by `detune` Hz
+    ///
+    /// The pair beats at `|detune|` Hz. With `ramp_to_zero` the detuned tone
+    /// glides onto the reference, so the beating slows and stops by the end,
+    /// the way it does when an instrument is tuned against a reference.
+    pub fn generate_zero_beat_file(
+        &self,
+        frequency: f64,
+        detune: f64,
+        ramp_to_zero: bool,
+    ) -> Result<(), SpiritError> {
+        let detuned = frequency + detune;
+        if !(frequency.is_finite() && detuned.is_finite()) || frequency <= 0.0 || detuned <= 0.0 {
+            return Err(invalid_frequency(format!(
+                "both tones must be positive (got {} Hz and {} Hz)",
+                frequency, detuned
+            )));
+        }
+        fs::create_dir_all(&self.output_dir).ok();
+
+        println!(
+            "\n=== Generating Zero-Beat Pair: {} Hz + {} Hz ===",
+            frequency, detuned
+        );
+        if ramp_to_zero {
+            println!("  Beat: {:.2} Hz ramping to 0 Hz", detune.abs());
+        } else {
+            println!("  Beat: {:.2} Hz", detune.abs());
+        }
+
+        let reference = self.generate_sine_wave(frequency, self.duration);
+        let moving = if ramp_to_zero {
+            self.generate_glide(detuned, frequency, self.duration)
+        } else {
+            self.generate_sine_wave(detuned, self.duration)
+        };
+        let mut samples: Vec<f64> = reference
+            .iter()
+            .zip(&moving)
+            .map(|(a, b)| 0.5 * (a + b))
+            .collect();
+        self.apply_fade(&mut samples, 0.05);
+
+        let suffix = if ramp_to_zero { "_ramp" } else { "" };
+        let filename = format!("zero_beat_{:.0}hz_{:+}hz{}.wav", frequency, detune, suffix);
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Frequency of a MIDI note under the configured tuning
+    pub fn note_frequency(&self, midi: i32) -> f64 {
+        match self.tuning_system {
+            Some(system) => system.note_hz(midi),
+            None => self.tuning * 2f64.powf((midi - 69) as f64 / 12.0),
+        }
+    }
+
+    /// Generate a sine at a named note (e.g. `C4`) under the configured tuning
+    pub fn generate_note_file(&self, name: &str) -> Result<(), SpiritError> {
+        let midi = parse_note(name)
+            .ok_or_else(|| invalid_frequency(format!("invalid note name: {}", name)))?;
+        let frequency = self.note_frequency(midi);
+
+        fs::create_dir_all(&self.output_dir).ok();
+        println!("\n=== Generating Note {} ({:.2} Hz) ===", name, frequency);
+
+        let mut samples = self.generate_sine_wave(frequency, self.duration);
+        self.apply_envelope(&mut samples);
+        let filename = format!("note_{}_{:.2}hz.wav", name, frequency);
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate a singing bowl tone
+    pub fn generate_bowl_file(
+        &self,
+        frequency: f64,
+        decay: Option<f64>,
+    ) -> Result<(), SpiritError> {
+        if let Some(decay) = decay.filter(|&d| d <= 0.0 || d.is_nan()) {
+            return Err(invalid_input(format!(
+                "bowl decay must be greater than 0 seconds (got {})",
+                decay
+            )));
+        }
+        fs::create_dir_all(&self.output_dir).ok();
+
+        println!("\n=== Generating Singing Bowl: {} Hz ===", frequency);
+        let samples = self.generate_singing_bowl(frequency, self.duration, decay);
+        let filename = format!("bowl_{:.0}hz.wav", frequency);
+        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    }
+
+    /// Generate 40 Hz gamma entrainment audio and report the measured rate
+    pub fn generate_gamma40(&self, carrier: f64, mode: &GenerationMode) -> Result<(), SpiritError> {
+        const GAMMA_HZ: f64 = 40.0;
+
+        let dir = self.output_dir.join("gamma");
+        fs::create_dir_all(&dir).ok();
+
+        println!(
+            "\n=== Generating 40 Hz Gamma Entrainment ({:?}, {} Hz carrier) ===",
+            mode, carrier
+        );
+
+        let rate = self.config.sample_rate;
+        match mode {
+            GenerationMode::Sine => {
+                let samples = self.generate_sine_wave(GAMMA_HZ, self.duration);
+                let measured = analysis::dominant_frequency(&samples, rate, 20.0, 60.0);
+                println!("  Measured tone: {:.2} Hz", measured);
+                self.save_mono_wav(&dir.join("gamma_40hz_sine.wav"), &samples)
+            }
+            GenerationMode::Binaural => {
+                let samples = self.generate_binaural_beat(carrier, GAMMA_HZ, self.duration);
+                let summed: Vec<f64> = samples.iter().map(|[l, r]| l + r).collect();
+                let measured = analysis::modulation_frequency(&summed, rate, 20.0, 60.0);
+                println!("  Measured beat: {:.2} Hz", measured);
+                let filename = format!("gamma_40hz_binaural_{:.0}hz.wav", carrier);
+                self.save_stereo_wav(&dir.join(filename), &samples)
+            }
+            GenerationMode::Isochronic => {
+                let samples = self.generate_isochronic_tone(carrier, GAMMA_HZ, self.duration);
+                let measured = analysis::modulation_frequency(&samples, rate, 20.0, 60.0);
+                println!("  Measured modulation: {:.2} Hz", measured);
+                let filename = format!("gamma_40hz_isochronic_{:.0}hz.wav", carrier);
+                self.save_mono_wav(&dir.join(filename), &samples)
+            }
+        }
+    }
+
+    /// Benchmark sine generation and report the effective configuration
+    ///
+    /// Returns the measured throughput in samples per second.
+    pub fn run_doctor(&self) -> f64 {
+        const BENCH_SECS: f64 = 10.0;
+        const HOUR_SECS: f64 = 3600.0;
+
+        println!("\n=== Spirit Doctor ===");
+        println!("  Sample rate:  {} Hz", self.config.sample_rate);
+        println!(
+            "  Bit depth:    {}-bit {}",
+            self.config.bit_depth,
+            if self.config.float {
+                "float"
+            } else {
+                "integer"
+            }
+        );
+        println!(
+            "  Nyquist:      {} Hz",
+            self.config.sample_rate as f64 / 2.0
+        );
+        println!("  Output dir:   {}", self.output_dir.display());
+
+        let start = Instant::now();
+        let samples = self.generate_sine_wave(440.0, BENCH_SECS);
+        let elapsed = start.elapsed().as_secs_f64().max(f64::EPSILON);
+        let throughput = samples.len() as f64 / elapsed;
+
+        println!(
+            "  Benchmark:    {} samples ({} s sine) in {:.3} s",
+            samples.len(),
+            BENCH_SECS,
+            elapsed
+        );
+        println!(
+            "  Throughput:   {:.0} samples/sec ({:.1}x realtime)",
+            throughput,
+            throughput / self.config.sample_rate as f64
+        );
+
+        let hour_samples = self.config.sample_rate as f64 * HOUR_SECS;
+        let mib = |bytes: f64| bytes / (1024.0 * 1024.0);
+        println!(
+            "  1h render:    ~{:.0} MiB working memory (mono), ~{:.0} MiB stereo WAV, ~{:.0} s to generate",
+            mib(hour_samples * std::mem::size_of::<f64>() as f64),
+            mib(self.config.estimated_wav_bytes(HOUR_SECS, 2) as f64),
+            hour_samples / throughput
+        );
+        throughput
+    }
+
+    /// Choose the carrier for a binaural beat or isochronic pulse at `beat` Hz
+    ///
+    /// An explicit carrier is kept as given, with a warning when it is under
+    /// ten times the beat. Otherwise the default 200 Hz carrier is raised to
+    /// ten times the beat if needed; with `snap_to_note` the result is moved
+    /// to the nearest equal-tempered note (G3, 196 Hz, at A4 = 440).
+    pub fn pick_carrier(&self, beat: f64, requested: Option<f64>, snap_to_note: bool) -> f64 {
+        let minimum = beat.abs() * MIN_CARRIER_RATIO;
+        if let Some(carrier) = requested {
+            if carrier < minimum {
+                eprintln!(
+                    "  Warning: a {} Hz carrier is under {}x the {} Hz beat; try {} Hz or higher",
+                    carrier, MIN_CARRIER_RATIO, beat, minimum
+                );
+            }
+            return carrier;
+        }
+
+        let mut carrier = DEFAULT_CARRIER_HZ;
+        if minimum > carrier {
+            eprintln!(
+                "  Warning: {} Hz is too fast a beat for a {} Hz carrier, raising the carrier to {} Hz",
+                beat, carrier, minimum
+            );
+            carrier = minimum;
+        }
+        if snap_to_note {
+            // Round up to the next note if snapping would drop below the minimum
+            let a4 = self.tuning_system.map_or(self.tuning, TuningSystem::a4_hz);
+            let mut snapped = nearest_note_hz(carrier, a4);
+            if snapped < minimum {
+                snapped *= 2f64.powf(1.0 / 12.0);
+            }
+            carrier = snapped;
+        }
+        carrier
+    }
+
+    /// Generate a custom tone; binaural and isochronic modes use `carrier`
+    pub fn generate_custom(
+        &self,
+        frequency: f64,
+        mode: &GenerationMode,
+        carrier: f64,
+    ) -> Result<(), SpiritError> {
+        if !frequency.is_finite() {
+            return Err(invalid_frequency(format!(
+                "frequency must be a finite number (got {})",
+                frequency
+            )));
+        }
+
+        fs::create_dir_all(&self.output_dir).ok();
+
+        match mode {
+            GenerationMode::Sine => {
+                println!("\n=== Generating Custom {} Hz ({:?}) ===", frequency, mode)
+            }
+            _ => println!(
+                "\n=== Generating Custom {} Hz ({:?}, {:.2} Hz carrier) ===",
+                frequency, mode, carrier
+            ),
+        }
+
+        match mode {
+            GenerationMode::Sine => {
+                let mut samples = self.generate_sine_wave(frequency, self.duration);
+                self.apply_envelope(&mut samples);
+                let path = self
+                    .output_dir
+                    .join(format!("custom_{:.2}hz_sine.wav", frequency));
+                self.save_mono_wav(&path, &samples)
+            }
+            GenerationMode::Binaural => {
+                let mut samples = self.generate_binaural_beat(carrier, frequency, self.duration);
+                self.apply_envelope_stereo(&mut samples);
+                let path = self
+                    .output_dir
+                    .join(format!("custom_{:.2}hz_binaural.wav", frequency));
+                self.save_stereo_wav(&path, &samples)
+            }
+            GenerationMode::Isochronic => {
+                let mut samples = self.generate_isochronic_tone(carrier, frequency, self.duration);
+                self.apply_envelope(&mut samples);
+                let path = self
+                    .output_dir
+                    .join(format!("custom_{:.2}hz_isochronic.wav", frequency));
+                self.save_mono_wav(&path, &samples)
+            }
+        }
+    }
+
+    /// Generate a custom tone written identically to every one of `channels` channels
+    ///
+    /// Useful as a per-speaker test tone; unlike surround panning, every
+    /// channel carries the same signal at full level.
+    pub fn generate_custom_duplicated(
+        &self,
+        frequency: f64,
+        mode: &GenerationMode,
+        carrier: f64,
+        channels: u16,
+    ) -> Result<(), SpiritError> {
+        if !frequency.is_finite() {
+            return Err(invalid_frequency(format!(
+                "frequency must be a finite number (got {})",
+                frequency
+            )));
+        }
+        let mut samples = match mode {
+            GenerationMode::Sine => self.generate_sine_wave(frequency, self.duration),
+            GenerationMode::Isochronic => {
+                self.generate_isochronic_tone(carrier, frequency, self.duration)
+            }
+            GenerationMode::Binaural => {
+                return Err(invalid_input(
+                    "binaural output needs distinct channels and cannot be duplicated".to_string(),
+                ))
+            }
+        };
+
+        fs::create_dir_all(&self.output_dir).ok();
+        println!(
+            "\n=== Generating Custom {} Hz ({:?}) on {} Channels ===",
+            frequency, mode, channels
+        );
+
+        self.apply_envelope(&mut samples);
+        let path = self.output_dir.join(format!(
+            "custom_{:.2}hz_{}_{}ch.wav",
+            frequency,
+            format!("{:?}", mode).to_lowercase(),
+            channels
+        ));
+
+        // Two channels take the stereo path so balance, swap and width still apply
+        if channels == 2 {
+            let frames: Vec<[f64; 2]> = samples.iter().map(|&s| [s, s]).collect();
+            return self.save_stereo_wav(&path, &frames);
+        }
+        let interleaved: Vec<f64> = samples
+            .iter()
+            .flat_map(|&sample| std::iter::repeat_n(sample, channels as usize))
+            .collect();
+        self.write_wav(&path, channels, &interleaved)
+    }
+}
+
+/// Generation mode for custom frequencies
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum GenerationMode {
+    /// Plain sine tone at the frequency
+    Sine,
+    /// Carrier (200 Hz by default) with the frequency as the left/right offset (headphones)
+    Binaural,
+    /// Carrier (200 Hz by default) pulsed at the frequency (works on speakers)
+    Isochronic,
+}
+
+impl GenerationMode {
+    /// Number of channels the mode writes
+    pub fn channels(self) -> u16 {
+        match self {
+            GenerationMode::Binaural => 2,
+            GenerationMode::Sine | GenerationMode::Isochronic => 1,
+        }
+    }
+}
+
+/// Measurement signal for `test-signal`
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum TestSignal {
+    /// A single full-scale sample at the midpoint
+    Impulse,
+    /// Silence, then full scale from the midpoint on
+    Step,
+    /// Linear frequency chirp across a band
+    Chirp,
+}
+
+/// Which frequencies of a category comparison to render
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum SetOperation {
+    /// Frequencies found in both categories
+    Intersection,
+    /// Every frequency from either category, shared ones once
+    Union,
+}
+
+/// Which point of a brainwave band to target
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum BandEdge {
+    /// Lower edge of the band
+    Low,
+    /// Midpoint of the band
+    Mid,
+    /// Upper edge of the band
+    High,
+}
+
+impl BandEdge {
+    /// Target frequency within the state's band
+    pub fn frequency(self, state: &BrainwaveState) -> f64 {
+        match self {
+            BandEdge::Low => state.low_hz,
+            BandEdge::Mid => (state.low_hz + state.high_hz) / 2.0,
+            BandEdge::High => state.high_hz,
+        }
+    }
+}
+
+/// Noise spectrum color
+#[derive(Debug, Clone, Copy, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoiseColor {
+    /// Flat spectrum
+    White,
+    /// 1/f spectrum (-3 dB/octave)
+    Pink,
+    /// 1/f² spectrum (-6 dB/octave)
+    Brown,
+}
+
+impl NoiseColor {
+    /// Lowercase name used in filenames
+    pub fn name(self) -> &'static str {
+        match self {
+            NoiseColor::White => "white",
+            NoiseColor::Pink => "pink",
+            NoiseColor::Brown => "brown",
+        }
+    }
+}
+
+/// Curve shape for fade-in/out ramps
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum FadeWindow {
+    /// Straight-line ramp
+    Linear,
+    /// Raised-cosine ramp that starts and ends flat
+    Hann,
+    /// Raised-cosine ramps around a flat center section
+    Tukey,
+}
+
+impl FadeWindow {
+    /// Gain at `position` (0..=1) along a fade-in ramp
+    fn ramp(self, position: f64) -> f64 {
+        match self {
+            FadeWindow::Linear => position,
+            FadeWindow::Hann | FadeWindow::Tukey => 0.5 * (1.0 - (PI * position).cos()),
+        }
+    }
+}
+
+/// Load a single-cycle wavetable from a WAV (first channel) or CSV file
+///
+/// CSV tables may separate values with commas, whitespace, or newlines.
+fn load_wavetable(path: &Path) -> Result<Vec<f64>, SpiritError> {
+    let is_wav = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"));
+
+    let table: Vec<f64> = if is_wav {
+        let (channels, _, samples) = wav::read_wav(path)?;
+        samples.into_iter().step_by(channels as usize).collect()
+    } else {
+        fs::read_to_string(path)?
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|v| !v.is_empty())
+            .map(|v| {
+                v.parse::<f64>()
+                    .map_err(|_| invalid_input(format!("invalid wavetable value: {}", v)))
+            })
+            .collect::<Result<_, _>>()?
+    };
+
+    if table.is_empty() {
+        return Err(invalid_input(format!(
+            "wavetable {} is empty",
+            path.display()
+        )));
+    }
+    Ok(table)
+}
+
+/// Raised-sine pulse envelope at `phase` (0-1) with the given duty cycle
+///
+/// The rising half of the pulse is stretched over `duty` of the period and the
+/// falling half over the rest; a duty of 0.5 is a plain raised sine.
+fn isochronic_envelope(phase: f64, duty: f64) -> f64 {
+    let duty = duty.clamp(0.0, 1.0);
+    let warped = if phase < duty {
+        0.5 * phase / duty
+    } else {
+        0.5 + 0.5 * (phase - duty) / (1.0 - duty)
+    };
+    (0.5 * (1.0 + (2.0 * PI * warped).sin())).clamp(0.0, 1.0)
+}
+
+/// Split two categories' nonzero frequencies into `(shared, only first, only second)`
+///
+/// Frequencies within 0.01 Hz count as shared; shared entries come from `first`.
+fn compare_categories(
+    first: Category,
+    second: Category,
+) -> (
+    Vec<&'static FrequencyInfo>,
+    Vec<&'static FrequencyInfo>,
+    Vec<&'static FrequencyInfo>,
+) {
+    const SAME_HZ: f64 = 0.01;
+
+    let tones = |category: Category| -> Vec<&'static FrequencyInfo> {
+        category
+            .frequencies()
+            .iter()
+            .filter(|f| f.hz > 0.0)
+            .collect()
+    };
+    let (a, b) = (tones(first), tones(second));
+    let in_set = |set: &[&FrequencyInfo], hz: f64| set.iter().any(|f| (f.hz - hz).abs() < SAME_HZ);
+
+    let (shared, only_a) = a.iter().partition(|f| in_set(&b, f.hz));
+    let only_b = b.iter().copied().filter(|f| !in_set(&a, f.hz)).collect();
+    (shared, only_a, only_b)
+}
+
+/// Scale each segment to the RMS level of the quietest one
+///
+/// Matching down rather than up keeps every segment below its original peak.
+fn match_segment_rms(segments: &mut [Vec<f64>]) {
+    let target = segments
+        .iter()
+        .map(|segment| analysis::rms(segment))
+        .filter(|&rms| rms > 0.0)
+        .fold(f64::INFINITY, f64::min);
+    if !target.is_finite() {
+        return;
+    }
+
+    for segment in segments.iter_mut() {
+        let rms = analysis::rms(segment);
+        if rms > 0.0 {
+            let gain = target / rms;
+            segment.iter_mut().for_each(|s| *s *= gain);
+        }
+    }
+}
+
+/// Constant-power `[left, right]` gains for a pan position from -1.0 to 1.0
+fn pan_gains(pan: f64) -> [f64; 2] {
+    let angle = (pan.clamp(-1.0, 1.0) + 1.0) * PI / 4.0;
+    [angle.cos(), angle.sin()]
+}
+
+/// Build an error for invalid user-supplied parameters
+fn invalid_input(message: String) -> SpiritError {
+    SpiritError::InvalidInput(message)
+}
+
+fn invalid_frequency(message: String) -> SpiritError {
+    SpiritError::InvalidFrequency(message)
+}
+
+fn unsupported_format(message: String) -> SpiritError {
+    SpiritError::UnsupportedFormat(message)
+}
+
+/// Average stereo frames into a single channel
+fn downmix(frames: &[[f64; 2]]) -> Vec<f64> {
+    frames
+        .iter()
+        .map(|&[left, right]| (left + right) / 2.0)
+        .collect()
+}
+
+/// Encode a stereo frame as `[mid, side]`, preserving energy
+fn to_mid_side([left, right]: [f64; 2]) -> [f64; 2] {
+    [
+        (left + right) * FRAC_1_SQRT_2,
+        (left - right) * FRAC_1_SQRT_2,
+    ]
+}
+
+/// Decode `[mid, side]` back to a `[left, right]` frame
+fn from_mid_side([mid, side]: [f64; 2]) -> [f64; 2] {
+    [(mid + side) * FRAC_1_SQRT_2, (mid - side) * FRAC_1_SQRT_2]
+}
+
+/// Scale the Side component of each frame by `width`
+///
+/// 0 collapses to mono and 1 is unchanged. Widening can push peaks past the
+/// input's, so the result is then scaled back down to the original peak.
+fn apply_stereo_width(frames: &[[f64; 2]], width: f64) -> Vec<[f64; 2]> {
+    let peak = |frames: &[[f64; 2]]| {
+        frames
+            .iter()
+            .flatten()
+            .fold(0.0f64, |acc, s| acc.max(s.abs()))
+    };
+
+    let mut widened: Vec<[f64; 2]> = frames
+        .iter()
+        .map(|&frame| {
+            let [mid, side] = to_mid_side(frame);
+            from_mid_side([mid, side * width])
+        })
+        .collect();
+
+    let (before, after) = (peak(frames), peak(&widened));
+    if after > before {
+        let gain = before / after;
+        for sample in widened.iter_mut().flatten() {
+            *sample *= gain;
+        }
+    }
+    widened
+}
+
+/// Split interleaved samples into one buffer per channel
+fn deinterleave(samples: &[f64], channels: u16) -> Vec<Vec<f64>> {
+    let channels = channels as usize;
+    (0..channels)
+        .map(|c| samples.iter().skip(c).step_by(channels).copied().collect())
+        .collect()
+}
+
+/// Interleave per-channel buffers into a single frame-ordered buffer
+fn interleave(channels: &[Vec<f64>]) -> Vec<f64> {
+    let frames = channels.iter().map(Vec::len).min().unwrap_or(0);
+    (0..frames)
+        .flat_map(|i| channels.iter().map(move |channel| channel[i]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frequency::parse_frequency_filename;
+    use crate::test_support;
+
+    #[test]
+    fn sweep_rejects_zero_start() {
+        let gen = test_support::generator("sweep-zero", 0.1);
+        assert!(matches!(
+            gen.validate_sweep_endpoints(0.0, 1000.0),
+            Err(SpiritError::InvalidFrequency(_))
+        ));
+        assert!(gen.generate_frequency_sweep_file(0.0, 1000.0).is_err());
+    }
+
+    #[test]
+    fn sweep_rejects_negative_endpoints() {
+        let gen = test_support::generator("sweep-negative", 0.1);
+        assert!(gen.validate_sweep_endpoints(-20.0, 1000.0).is_err());
+        assert!(gen.validate_sweep_endpoints(20.0, -1000.0).is_err());
+    }
+
+    #[test]
+    fn sweep_clamps_endpoints_above_nyquist() {
+        let gen = test_support::generator("sweep-nyquist", 0.1);
+        let (start, end) = gen.validate_sweep_endpoints(20.0, 30000.0).unwrap();
+        assert_eq!(start, 20.0);
+        assert_eq!(end, 22050.0);
+    }
+
+    #[test]
+    fn split_layers_put_first_tone_on_the_left() {
+        let gen = test_support::generator("split-layers", 0.1);
+        let frames = gen.generate_split_layers(&[200.0, 300.0], 0.1);
+        let left: Vec<f64> = frames.iter().map(|frame| frame[0]).collect();
+        let right: Vec<f64> = frames.iter().map(|frame| frame[1]).collect();
+        assert_eq!(left, gen.generate_sine_wave(200.0, 0.1));
+        assert_eq!(right, gen.generate_sine_wave(300.0, 0.1));
+    }
+
+    #[test]
+    fn gamma40_modulates_at_40_hz() {
+        let gen = test_support::generator("gamma40", 2.0);
+        let rate = gen.config.sample_rate;
+
+        let pulsed = gen.generate_isochronic_tone(200.0, 40.0, 2.0);
+        let measured = analysis::modulation_frequency(&pulsed, rate, 20.0, 60.0);
+        assert!(
+            (measured - 40.0).abs() < 0.25,
+            "isochronic at {} Hz",
+            measured
+        );
+
+        let beat = gen.generate_binaural_beat(200.0, 40.0, 2.0);
+        let summed: Vec<f64> = beat.iter().map(|[l, r]| l + r).collect();
+        let measured = analysis::modulation_frequency(&summed, rate, 20.0, 60.0);
+        assert!(
+            (measured - 40.0).abs() < 0.25,
+            "binaural at {} Hz",
+            measured
+        );
+    }
+
+    #[test]
+    fn append_extends_an_existing_file() {
+        let mut gen = test_support::generator("append", 5.0);
+        let path = gen.output_dir.join("custom_100.00hz_sine.wav");
+        gen.generate_custom(100.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ)
+            .unwrap();
+
+        gen.append = true;
+        gen.generate_custom(100.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ)
+            .unwrap();
+
+        let (channels, rate, samples) = test_support::read(&path);
+        assert_eq!(channels, 1);
+        assert_eq!(samples.len(), 10 * rate as usize);
+    }
+
+    #[test]
+    fn append_rejects_a_different_format() {
+        let mut gen = test_support::generator("append-format", 0.5);
+        gen.generate_custom(100.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ)
+            .unwrap();
+
+        gen.append = true;
+        gen.config.bit_depth = 24;
+        assert!(matches!(
+            gen.generate_custom(100.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ),
+            Err(SpiritError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn hann_fade_starts_with_zero_slope() {
+        let step = 1e-4;
+        let hann_slope = FadeWindow::Hann.ramp(step) / step;
+        let linear_slope = FadeWindow::Linear.ramp(step) / step;
+        assert_eq!(FadeWindow::Hann.ramp(0.0), 0.0);
+        assert!(hann_slope < 1e-3, "Hann slope {}", hann_slope);
+        assert!((linear_slope - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hann_fade_in_reaches_full_level() {
+        let mut gen = test_support::generator("hann-fade", 1.0);
+        gen.fade_window = FadeWindow::Hann;
+        let mut samples = vec![1.0; gen.sample_count(1.0)];
+        gen.apply_fade(&mut samples, 0.1);
+
+        let fade = gen.fade_samples(samples.len(), 0.1);
+        assert_eq!(samples[0], 0.0);
+        assert!(samples[1] < 1e-4);
+        assert_eq!(samples[fade], 1.0);
+    }
+
+    #[test]
+    fn harmonics_above_nyquist_are_excluded() {
+        let mut gen = test_support::generator("harmonics-nyquist", 0.1);
+        gen.config.sample_rate = 8000;
+        let partials = gen.harmonic_series(1000.0, 9, 0.0);
+        let frequencies: Vec<f64> = partials.iter().map(|&(freq, _)| freq).collect();
+        assert_eq!(frequencies, vec![1000.0, 2000.0, 3000.0]);
+        assert_eq!(partials[2].1, 1.0 / 3.0);
+    }
+
+    #[test]
+    fn f64_output_preserves_generated_samples() {
+        let mut gen = test_support::generator("f64-output", 0.25);
+        gen.config.bit_depth = 64;
+        gen.config.float = true;
+        gen.generate_custom(440.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ)
+            .unwrap();
+
+        let expected = gen.generate_sine_wave(440.0, 0.25);
+        let path = gen.output_dir.join("custom_440.00hz_sine.wav");
+        let (_, _, samples) = test_support::read(&path);
+        assert_eq!(samples, expected);
+    }
+
+    #[test]
+    fn non_finite_samples_are_written_as_silence_and_counted() {
+        let mut gen = test_support::generator("non-finite", 0.1);
+        let input = [0.5, f64::NAN, -0.5, f64::INFINITY];
+
+        let path = gen.output_dir.join("direct.wav");
+        gen.save_mono_wav(&path, &input).unwrap();
+        let (_, _, samples) = test_support::read(&path);
+        assert_eq!((samples[1], samples[3]), (0.0, 0.0));
+        assert!(samples[0] > 0.49 && samples[2] < -0.49);
+        assert_eq!(gen.replaced_samples(), 2);
+
+        // The same samples through the effects chain
+        gen.effects = vec![effects::Effect::Limiter(0.9)];
+        let path = gen.output_dir.join("processed.wav");
+        gen.save_mono_wav(&path, &input).unwrap();
+        let (_, _, samples) = test_support::read(&path);
+        assert_eq!((samples[1], samples[3]), (0.0, 0.0));
+        assert_eq!(gen.replaced_samples(), 4);
+    }
+
+    #[test]
+    fn solfeggio_extended_writes_each_octave_variant() {
+        let gen = test_support::generator("solfeggio-extended", 0.05);
+        gen.generate_solfeggio_extended().unwrap();
+
+        let files = test_support::wav_files(&gen.output_dir.join("solfeggio"));
+        let tones = Category::Solfeggio.frequencies();
+        assert_eq!(files.len(), tones.len() * 3);
+        for tone in tones {
+            for octave in 0..=2 {
+                let hz = format!("{:.2}hz", octave_transpose(tone.hz, octave));
+                assert!(
+                    files.iter().any(|f| f.to_string_lossy().contains(&hz)),
+                    "missing {}",
+                    hz
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sine_wavetable_reproduces_a_clean_sine() {
+        let gen = test_support::generator("wavetable", 1.0);
+        let table: Vec<f64> = (0..2048)
+            .map(|i| (2.0 * PI * i as f64 / 2048.0).sin())
+            .collect();
+
+        let rendered = gen.generate_wavetable(&table, 220.0, 1.0);
+        let reference = gen.generate_sine_wave(220.0, 1.0);
+        let max_error = rendered
+            .iter()
+            .zip(&reference)
+            .fold(0.0f64, |acc, (a, b)| acc.max((a - b).abs()));
+        assert!(max_error < 1e-4, "max error {}", max_error);
+
+        let measured =
+            analysis::dominant_frequency(&rendered, gen.config.sample_rate, 100.0, 1000.0);
+        assert!((measured - 220.0).abs() < 0.5, "peak at {} Hz", measured);
+    }
+
+    #[test]
+    fn wavetable_loads_from_csv() {
+        let dir = test_support::temp_dir("wavetable-csv");
+        let path = dir.join("table.csv");
+        fs::write(&path, "0, 1,0\n-1").unwrap();
+        assert_eq!(load_wavetable(&path).unwrap(), vec![0.0, 1.0, 0.0, -1.0]);
+    }
+
+    #[test]
+    fn intermod_file_contains_both_tones() {
+        let gen = test_support::generator("intermod", 1.0);
+        gen.generate_intermod_file(400.0, 500.0).unwrap();
+
+        let path = gen.output_dir.join("intermod_400hz_500hz.wav");
+        let (_, rate, samples) = test_support::read(&path);
+        let low = analysis::dominant_frequency(&samples, rate, 300.0, 450.0);
+        let high = analysis::dominant_frequency(&samples, rate, 450.0, 600.0);
+        assert!((low - 400.0).abs() < 1.0, "low tone at {} Hz", low);
+        assert!((high - 500.0).abs() < 1.0, "high tone at {} Hz", high);
+    }
+
+    #[test]
+    fn full_left_balance_mutes_the_right_channel() {
+        let mut gen = test_support::generator("balance", 0.5);
+        gen.balance = -1.0;
+        let frames = gen.generate_binaural_beat(200.0, 10.0, 0.5);
+        let path = gen.output_dir.join("balance.wav");
+        gen.save_stereo_wav(&path, &frames).unwrap();
+
+        let (channels, _, samples) = test_support::read(&path);
+        assert_eq!(channels, 2);
+        let left = test_support::channel(&samples, 2, 0);
+        let right = test_support::channel(&samples, 2, 1);
+        assert!(left.iter().any(|s| s.abs() > 0.1));
+        assert!(right.iter().all(|s| s.abs() < 1e-4));
+    }
+
+    #[test]
+    fn doctor_reports_positive_throughput() {
+        let gen = test_support::generator("doctor", 1.0);
+        assert!(gen.run_doctor() > 0.0);
+    }
+
+    #[test]
+    fn no_fade_starts_om_at_full_level() {
+        // sin(0) is zero either way, so look at the first couple of milliseconds
+        let onset = |samples: &[f64]| samples[..100].iter().fold(0.0f64, |m, s| m.max(s.abs()));
+
+        let mut gen = test_support::generator("no_fade", 2.0);
+        let faded = gen.generate_om_tone(2.0);
+        gen.no_fade = true;
+        let unfaded = gen.generate_om_tone(2.0);
+
+        assert!(unfaded[1] != 0.0);
+        assert!(onset(&unfaded) > 0.1, "{}", onset(&unfaded));
+        assert!(onset(&faded) < 0.01, "{}", onset(&faded));
+    }
+
+    #[test]
+    fn shorter_bowl_decay_is_quieter_later() {
+        let gen = test_support::generator("bowl-decay", 4.0);
+        let rate = gen.config.sample_rate as usize;
+        let window = |samples: &[f64]| analysis::rms(&samples[3 * rate..3 * rate + rate / 10]);
+
+        let short = gen.generate_singing_bowl(220.0, 4.0, Some(1.0));
+        let long = gen.generate_singing_bowl(220.0, 4.0, Some(10.0));
+        assert!(window(&short) < window(&long) * 0.2);
+    }
+
+    #[test]
+    fn random_phase_lowers_crest_factor() {
+        let mut gen = test_support::generator("random-phase", 1.0);
+        let harmonics: Vec<f64> = (1..=16).map(|k| 100.0 * k as f64).collect();
+
+        let in_phase = gen.generate_layered_frequencies(&harmonics, 1.0);
+        gen.random_phase = true;
+        let random = gen.generate_layered_frequencies(&harmonics, 1.0);
+
+        let (in_phase, random) = (
+            analysis::crest_factor_db(&in_phase),
+            analysis::crest_factor_db(&random),
+        );
+        assert!(random < in_phase - 1.0, "{} vs {} dB", random, in_phase);
+    }
+
+    #[test]
+    fn loudness_target_re_measures_near_target() {
+        let mut gen = test_support::generator("lufs", 3.0);
+        gen.target_lufs = Some(-20.0);
+        let path = gen.output_dir.join("lufs.wav");
+        gen.save_mono_wav(&path, &gen.generate_sine_wave(1000.0, 3.0))
+            .unwrap();
+
+        let (_, rate, samples) = test_support::read(&path);
+        let measured = analysis::integrated_loudness(&[samples], rate);
+        assert!((measured + 20.0).abs() < 0.5, "{} LUFS", measured);
+    }
+
+    #[test]
+    fn noise_burst_is_silent_between_bursts() {
+        let gen = test_support::generator("noise-burst", 4.0);
+        gen.generate_noise_burst_file(NoiseColor::Pink, 1.0, 1.0)
+            .unwrap();
+
+        let path = gen
+            .output_dir
+            .join("noise/noise_burst_pink_1s_on_1s_off.wav");
+        let (_, rate, samples) = test_support::read(&path);
+        let window = |from: f64, to: f64| {
+            analysis::rms(&samples[(from * rate as f64) as usize..(to * rate as f64) as usize])
+        };
+
+        for (on, off) in [(0.6, 1.1), (2.1, 3.1)] {
+            assert!(window(on, on + 0.3) > 0.05, "burst at {} s", on);
+            assert!(window(off, off + 0.8) < 1e-3, "gap at {} s", off);
+        }
+    }
+
+    #[test]
+    fn mixed_noise_level_zero_leaves_tone_unchanged() {
+        let gen = test_support::generator("mix-noise", 1.0);
+        let tone = gen.generate_sine_wave(440.0, 1.0);
+
+        let mut silent = tone.clone();
+        gen.mix_noise_into(&mut silent, NoiseColor::Pink, 0.0);
+        assert_eq!(silent, tone);
+
+        let mut mixed = tone.clone();
+        gen.mix_noise_into(&mut mixed, NoiseColor::Pink, 0.2);
+        assert!(analysis::rms(&mixed) > analysis::rms(&tone));
+    }
+
+    #[test]
+    fn swell_rms_follows_the_requested_period() {
+        let gen = test_support::generator("swell", 6.0);
+        let rate = gen.config.sample_rate as f64;
+        let mut samples = gen.generate_sine_wave(220.0, 6.0);
+        gen.apply_swell(&mut samples, 2.0, 0.8);
+
+        let rms_at = |t: f64| {
+            let center = (t * rate) as usize;
+            let half = (0.05 * rate) as usize;
+            analysis::rms(&samples[center.saturating_sub(half)..center + half])
+        };
+        for peak in [2.0, 4.0] {
+            for trough in [peak - 1.0, peak + 1.0] {
+                assert!(
+                    rms_at(trough) < rms_at(peak) * 0.3,
+                    "{} s vs {} s",
+                    trough,
+                    peak
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cancel_flag_stops_before_the_next_file() {
+        let gen = test_support::generator("cancel", 0.5);
+        gen.generate_custom(100.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ)
+            .unwrap();
+
+        gen.cancel.store(true, Ordering::SeqCst);
+        assert!(matches!(
+            gen.generate_custom(200.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ),
+            Err(SpiritError::Cancelled)
+        ));
+
+        assert_eq!(gen.saved_files(), 1);
+        assert!(!gen.output_dir.join("custom_200.00hz_sine.wav").exists());
+        let (_, rate, samples) =
+            test_support::read(&gen.output_dir.join("custom_100.00hz_sine.wav"));
+        assert_eq!(samples.len(), rate as usize / 2);
+    }
+
+    #[test]
+    fn multi_binaural_spectrum_holds_each_carrier_pair() {
+        let gen = test_support::generator("multi-binaural", 2.0);
+        let rate = gen.config.sample_rate;
+        let frames = gen.generate_multi_binaural(&[100.0, 200.0, 300.0], 10.0, 2.0);
+        let left: Vec<f64> = frames.iter().map(|frame| frame[0]).collect();
+        let right: Vec<f64> = frames.iter().map(|frame| frame[1]).collect();
+
+        for carrier in [100.0, 200.0, 300.0] {
+            let measured =
+                analysis::dominant_frequency(&left, rate, carrier - 20.0, carrier + 20.0);
+            assert!((measured - carrier).abs() < 0.5, "left {}", measured);
+            let measured =
+                analysis::dominant_frequency(&right, rate, carrier - 20.0, carrier + 30.0);
+            assert!(
+                (measured - carrier - 10.0).abs() < 0.5,
+                "right {}",
+                measured
+            );
+        }
+    }
+
+    #[test]
+    fn multi_binaural_rejects_invalid_carriers_and_beats() {
+        let gen = test_support::generator("multi-binaural-invalid", 0.1);
+        for (carriers, beat) in [
+            (vec![100.0, f64::NAN], 10.0),
+            (vec![100.0, -200.0], 10.0),
+            (vec![100.0, 22_050.0], 10.0),
+            (vec![21_000.0], 2_000.0),
+            (vec![100.0], 0.0),
+            (vec![100.0], f64::INFINITY),
+        ] {
+            assert!(
+                matches!(
+                    gen.generate_multi_binaural_file(&carriers, beat),
+                    Err(SpiritError::InvalidFrequency(_))
+                ),
+                "{:?} with {} Hz beat",
+                carriers,
+                beat
+            );
+        }
+        assert!(test_support::wav_files(&gen.output_dir).is_empty());
+    }
+
+    #[test]
+    fn panorama_is_stereo_and_sums_every_tone() {
+        let gen = test_support::generator("panorama", 0.5);
+        gen.generate_panorama(Category::Chakras).unwrap();
+
+        let path = gen
+            .output_dir
+            .join("panorama")
+            .join(format!("{}_panorama.wav", Category::Chakras.dir_name()));
+        let (channels, _, samples) = test_support::read(&path);
+        assert_eq!(channels, 2);
+        assert_ne!(
+            test_support::channel(&samples, 2, 0),
+            test_support::channel(&samples, 2, 1)
+        );
+
+        let voices: Vec<(Vec<f64>, f64)> = [(256.0, -1.0), (288.0, 0.0), (320.0, 1.0)]
+            .iter()
+            .map(|&(hz, pan)| (gen.generate_sine_wave(hz, 0.5), pan))
+            .collect();
+        // Correlating the right channel against the hard-right tone isolates its share
+        let reference = gen.generate_sine_wave(320.0, 0.5);
+        let share = |frames: Vec<[f64; 2]>| -> f64 {
+            frames
+                .iter()
+                .zip(&reference)
+                .map(|(frame, r)| frame[1] * r)
+                .sum()
+        };
+        assert!(share(gen.mix_panned(&voices)) > 100.0);
+        assert!(share(gen.mix_panned(&voices[..2])).abs() < 1.0);
+    }
+
+    #[test]
+    fn degenerate_sweep_writes_no_nan() {
+        let gen = test_support::generator("sweep-degenerate", 0.5);
+        gen.generate_frequency_sweep_file(440.0, 440.0).unwrap();
+
+        let files = test_support::wav_files(&gen.output_dir);
+        assert_eq!(files.len(), 1);
+        let (_, _, samples) = test_support::read(&files[0]);
+        assert!(samples.iter().all(|s| s.is_finite()));
+        assert!(analysis::rms(&samples) > 0.1);
+    }
+
+    #[test]
+    fn brainwave_tones_write_one_file_per_state() {
+        let gen = test_support::generator("brainwaves", 0.25);
+        gen.generate_brainwave_tones(&GenerationMode::Sine).unwrap();
+
+        let files = test_support::wav_files(&gen.output_dir.join("brainwaves"));
+        assert_eq!(files.len(), BRAINWAVE_STATES.len());
+        for state in BRAINWAVE_STATES {
+            let midpoint = (state.low_hz + state.high_hz) / 2.0;
+            let mode = if midpoint < 20.0 {
+                "isochronic"
+            } else {
+                "sine"
+            };
+            let name = format!("brainwave_{}_{:.2}hz_{}.wav", state.name, midpoint, mode);
+            assert!(files.iter().any(|path| path.ends_with(&name)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn matched_segments_share_one_rms() {
+        let gen = test_support::generator("segment-rms", 1.0);
+        let mut segments = vec![
+            gen.generate_sine_wave(220.0, 1.0),
+            gen.generate_layered_frequencies(&[300.0, 450.0, 600.0], 1.0),
+            gen.generate_noise(NoiseColor::Pink, 1.0),
+            vec![0.0; 100],
+        ];
+        match_segment_rms(&mut segments);
+
+        let levels: Vec<f64> = segments[..3].iter().map(|s| analysis::rms(s)).collect();
+        for level in &levels {
+            assert!((level - levels[0]).abs() < 1e-9, "{:?}", levels);
+        }
+        assert!(segments[3].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn audio_index_has_a_cue_per_category() {
+        let gen = test_support::generator("audio-index", 1.0);
+        gen.generate_audio_index(0.1).unwrap();
+
+        let bytes = fs::read(gen.output_dir.join("audio_index.wav")).unwrap();
+        let chunk = bytes
+            .windows(4)
+            .rposition(|id| id == b"cue ")
+            .expect("cue chunk");
+        let count = u32::from_le_bytes(bytes[chunk + 8..chunk + 12].try_into().unwrap());
+
+        let categories = Category::all()
+            .iter()
+            .filter(|category| category.frequencies().iter().any(|f| f.hz > 0.0))
+            .count();
+        assert_eq!(count as usize, categories);
+    }
+
+    #[test]
+    fn duty_sets_the_fraction_above_half_level() {
+        const STEPS: usize = 10_000;
+        let above_half = |duty: f64| {
+            (0..STEPS)
+                .filter(|&i| isochronic_envelope(i as f64 / STEPS as f64, duty) > 0.5)
+                .count() as f64
+                / STEPS as f64
+        };
+
+        for duty in [0.25, 0.5, 0.75] {
+            assert!((above_half(duty) - duty).abs() < 0.01, "duty {}", duty);
+        }
+    }
+
+    #[test]
+    fn sampler_writes_a_clip_per_nonzero_category() {
+        let gen = test_support::generator("sampler", 1.0);
+        gen.generate_sampler(0.1).unwrap();
+
+        let expected = Category::all()
+            .iter()
+            .filter(|category| category.frequencies().first().is_some_and(|f| f.hz > 0.0))
+            .count();
+        let clips = test_support::wav_files(&gen.output_dir.join("sampler"));
+        assert_eq!(clips.len(), expected);
+    }
+
+    #[test]
+    fn cue_sheet_has_a_track_per_segment() {
+        let mut gen = test_support::generator("cue-sheet", 1.0);
+        gen.cue_sheet = true;
+        let titles: Vec<String> = ["one", "two", "three"].map(String::from).to_vec();
+        let segments: Vec<Vec<f64>> = [220.0, 330.0, 440.0]
+            .iter()
+            .map(|&hz| gen.generate_sine_wave(hz, 0.5))
+            .collect();
+        let path = gen.output_dir.join("sequence.wav");
+        gen.save_sequence(&path, "Sequence", &titles, &segments, false)
+            .unwrap();
+
+        let sheet = fs::read_to_string(path.with_extension("cue")).unwrap();
+        assert_eq!(sheet.matches("TRACK").count(), segments.len());
+        assert!(sheet.contains("INDEX 01 00:01:00"));
+    }
+
+    #[test]
+    fn phase_accumulator_holds_frequency_late_in_long_buffers() {
+        const SECS: f64 = 600.0;
+        let config = AudioConfig {
+            sample_rate: 8000,
+            ..AudioConfig::default()
+        };
+        let mut gen = AudioGenerator::new(test_support::temp_dir("phase-acc"), SECS, config);
+        let tail = |samples: Vec<f64>| samples[samples.len() - 8000..].to_vec();
 
-        println!("\n=== Generating Schumann Resonance (7.83 Hz) ===");
+        let closed_form = tail(gen.generate_sine_wave(1001.7, SECS));
+        gen.phase_accumulator = true;
+        let accumulated = tail(gen.generate_sine_wave(1001.7, SECS));
 
-        println!("  Isochronic tone (works without headphones)");
-        let samples = self.generate_isochronic_tone(200.0, 7.83, self.duration);
-        self.save_mono_wav(&dir.join("schumann_7.83hz_isochronic.wav"), &samples)?;
+        for samples in [&closed_form, &accumulated] {
+            let measured = analysis::dominant_frequency(samples, 8000, 900.0, 1100.0);
+            assert!((measured - 1001.7).abs() < 0.1, "{} Hz", measured);
+        }
+        let drift = closed_form
+            .iter()
+            .zip(&accumulated)
+            .fold(0.0f64, |acc, (a, b)| acc.max((a - b).abs()));
+        assert!(drift < 1e-3, "{}", drift);
+    }
 
-        println!("  Binaural beat (requires headphones)");
-        let samples = self.generate_binaural_beat(200.0, 7.83, self.duration);
-        self.save_stereo_wav(&dir.join("schumann_7.83hz_binaural.wav"), &samples)?;
+    #[test]
+    fn simultaneous_tuning_comparison_beats_at_8_hz() {
+        let gen = test_support::generator("tuning-simultaneous", 2.0);
+        gen.generate_tuning_comparison(true).unwrap();
 
-        Ok(())
+        let path = gen
+            .output_dir
+            .join("tuning/tuning_432_440_simultaneous.wav");
+        let (_, rate, samples) = test_support::read(&path);
+        let measured = analysis::modulation_frequency(&samples, rate, 2.0, 20.0);
+        assert!((measured - 8.0).abs() < 0.25, "{} Hz", measured);
     }
 
-    /// Generate chakra meditation sequence
-    pub fn generate_chakra_meditation(&self) -> Result<(), hound::Error> {
-        let dir = self.output_dir.join("chakras");
-        fs::create_dir_all(&dir).ok();
+    #[test]
+    fn sidechain_gain_rises_with_louder_noise() {
+        let gen = test_support::generator("auto-gain", 4.0);
+        let rate = gen.config.sample_rate as usize;
+        let tone = gen.generate_sine_wave(440.0, 4.0);
+        let noise: Vec<f64> = gen
+            .generate_noise(NoiseColor::White, 4.0)
+            .iter()
+            .enumerate()
+            .map(|(i, n)| if i < 2 * rate { 0.02 * n } else { 0.4 * n })
+            .collect();
 
-        println!("\n=== Generating Chakra Meditation Sequence ===");
+        let mut shaped = tone.clone();
+        gen.apply_sidechain_gain(&mut shaped, &noise);
+        let gain = |from: usize| {
+            let window = from..from + rate / 2;
+            analysis::rms(&shaped[window.clone()]) / analysis::rms(&tone[window])
+        };
+        assert!(
+            gain(3 * rate) > gain(rate) * 1.2,
+            "{} vs {}",
+            gain(3 * rate),
+            gain(rate)
+        );
+    }
 
-        let mut full_sequence: Vec<f64> = Vec::new();
+    #[test]
+    fn band_edges_pick_the_alpha_limits() {
+        let gen = test_support::generator("band-edge", 2.0);
+        let alpha = BRAINWAVE_STATES.iter().find(|s| s.name == "alpha").unwrap();
+        let dir = gen.output_dir.clone();
 
-        for freq_info in Category::Chakras.frequencies() {
-            println!(
-                "  {} ({} Hz): {}",
-                freq_info.name, freq_info.hz, freq_info.description
-            );
+        for (edge, expected) in [(BandEdge::Low, 8.0), (BandEdge::High, 14.0)] {
+            assert_eq!(edge.frequency(alpha), expected);
+            gen.generate_binaural_state(&dir, 200.0, alpha, edge)
+                .unwrap();
 
-            let mut samples = self.generate_sine_wave(freq_info.hz, self.duration);
-            self.apply_fade(&mut samples, 2.0);
+            let path = dir.join(format!("binaural_alpha_{:.1}hz.wav", expected));
+            let (_, rate, samples) = test_support::read(&path);
+            let summed: Vec<f64> = samples.chunks(2).map(|frame| frame[0] + frame[1]).collect();
+            let measured = analysis::modulation_frequency(&summed, rate, 2.0, 30.0);
+            assert!((measured - expected).abs() < 0.25, "{} Hz", measured);
+        }
+    }
 
-            let path = dir.join(format!(
-                "chakra_{}_{:.0}hz.wav",
-                freq_info.name, freq_info.hz
-            ));
-            self.save_mono_wav(&path, &samples)?;
+    #[test]
+    fn near_528_generates_every_matching_entry() {
+        let gen = test_support::generator("near", 0.1);
+        gen.generate_near(528.0, 0.5).unwrap();
 
-            full_sequence.extend_from_slice(&samples);
+        let files = test_support::wav_files(&gen.output_dir.join("near_528hz"));
+        assert!(files.len() > 1, "{:?}", files);
+        let solfeggio = format!("{}_", Category::Solfeggio.file_prefix());
+        assert!(files.iter().any(|path| path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with(&solfeggio)));
+    }
+
+    #[test]
+    fn category_gaps_count_toward_total_length() {
+        let gen = test_support::generator("category-gaps", 0.01);
+        gen.generate_all_categories_sequence(0.5).unwrap();
+
+        let tones: Vec<usize> = Category::all()
+            .iter()
+            .map(|&category| gen.category_segments(category).1.concat().len())
+            .filter(|&len| len > 0)
+            .collect();
+        let gaps = (tones.len() - 1) * gen.sample_count(0.5);
+        let expected = tones.iter().sum::<usize>() + gaps;
+
+        let (_, _, samples) = test_support::read(&gen.output_dir.join("all_sequence.wav"));
+        assert_eq!(samples.len(), expected);
+    }
+
+    #[test]
+    fn zero_beat_ramp_slows_to_a_standstill() {
+        let gen = test_support::generator("zero-beat", 8.0);
+        gen.generate_zero_beat_file(440.0, 4.0, true).unwrap();
+
+        let (_, rate, samples) =
+            test_support::read(&gen.output_dir.join("zero_beat_440hz_+4hz_ramp.wav"));
+        let at = |secs: f64| (secs * rate as f64) as usize;
+
+        // Each beat is a null in the 10 ms RMS envelope of the pair
+        let nulls = |from: f64, to: f64| {
+            let levels: Vec<f64> = samples[at(from)..at(to)]
+                .chunks(at(0.01))
+                .map(analysis::rms)
+                .collect();
+            let mut loud = false;
+            let mut count = 0;
+            for level in levels {
+                if level > 0.3 {
+                    loud = true;
+                } else if loud && level < 0.1 {
+                    loud = false;
+                    count += 1;
+                }
+            }
+            count
+        };
+
+        // The detune falls linearly from 4 Hz, so the beat phase reaches 7,
+        // 15 and 16 cycles at 2, 6 and 8 seconds
+        assert_eq!(nulls(0.0, 2.0), 7);
+        assert_eq!(nulls(4.0, 6.0), 3);
+        assert_eq!(nulls(7.0, 7.95), 0);
+    }
+
+    #[test]
+    fn beep_count_encodes_the_digits() {
+        let gen = test_support::generator("beeps", 1.0);
+        let onsets = |samples: &[f64]| test_support::onsets(samples, 88, 0.2);
+
+        for n in 1..=9 {
+            assert_eq!(onsets(&gen.count_beeps(n)), n as usize);
         }
+        assert_eq!(onsets(&gen.count_beeps(0)), 1);
 
-        println!("  Full meditation sequence...");
-        self.save_mono_wav(&dir.join("chakra_full_meditation.wav"), &full_sequence)?;
+        // 528 Hz: five beeps, a pause, then two
+        let prefix = gen.beep_prefix_for(528.0);
+        let first = gen.count_beeps(5).len() + gen.sample_count(0.4);
+        assert_eq!(onsets(&prefix[..first]), 5);
+        assert_eq!(onsets(&prefix[first..]), 2);
 
-        Ok(())
+        for hz in [7.83, 111.0, 528.0, 963.0] {
+            assert_eq!(gen.beep_prefix_len(hz), gen.beep_prefix_for(hz).len());
+        }
     }
 
-    /// Generate 432 Hz vs 440 Hz tuning comparison
-    pub fn generate_tuning_comparison(&self) -> Result<(), hound::Error> {
-        let dir = self.output_dir.join("tuning");
-        fs::create_dir_all(&dir).ok();
+    #[test]
+    fn calibration_sweep_starts_and_ends_at_its_endpoints() {
+        const SECS: f64 = 10.0;
+        const WINDOW: usize = 1 << 14;
+        let gen = test_support::generator("calibrate", SECS);
+        let rate = gen.config.sample_rate;
+        let frames = gen.generate_binaural_ramp(200.0, 1.0, 40.0, SECS);
+        let right: Vec<f64> = frames.iter().map(|frame| frame[1]).collect();
 
-        println!("\n=== Generating 432 Hz vs 440 Hz Comparison ===");
+        // Each window measures the beat at its center, half a window in from the edge
+        let inset = 39.0 * (WINDOW as f64 / 2.0) / rate as f64 / SECS;
+        let start = analysis::dominant_frequency(&right[..WINDOW], rate, 150.0, 300.0);
+        let end = analysis::dominant_frequency(&right[right.len() - WINDOW..], rate, 150.0, 300.0);
+        assert!((start - (201.0 + inset)).abs() < 1.0, "start {} Hz", start);
+        assert!((end - (240.0 - inset)).abs() < 1.0, "end {} Hz", end);
+    }
 
-        let samples_432 = self.generate_sine_wave(432.0, self.duration);
-        let samples_440 = self.generate_sine_wave(440.0, self.duration);
+    #[test]
+    fn entries_above_nyquist_are_skipped_and_reported() {
+        let config = AudioConfig {
+            sample_rate: 8000,
+            ..AudioConfig::default()
+        };
+        let gen = AudioGenerator::new(test_support::temp_dir("nyquist-skip"), 0.05, config);
+        gen.generate_category(Category::Rife).unwrap();
 
-        self.save_mono_wav(&dir.join("tuning_432hz_natural.wav"), &samples_432)?;
-        self.save_mono_wav(&dir.join("tuning_440hz_standard.wav"), &samples_440)?;
+        let (high, low): (Vec<&FrequencyInfo>, Vec<&FrequencyInfo>) = Category::Rife
+            .frequencies()
+            .iter()
+            .filter(|f| f.hz > 0.0)
+            .partition(|f| f.hz >= 4000.0);
+        assert!(!high.is_empty());
 
-        println!("  A-B comparison (alternating)...");
-        let segment_duration = 5.0;
-        let num_segments = (self.duration / (segment_duration * 2.0)) as usize;
-        let mut comparison: Vec<f64> = Vec::new();
+        let skipped = gen.skipped_frequencies();
+        assert_eq!(skipped.len(), high.len());
+        for f in &high {
+            assert!(skipped
+                .iter()
+                .any(|(name, hz)| name == f.name && *hz == f.hz));
+        }
+        let files = test_support::wav_files(&gen.output_dir.join(Category::Rife.dir_name()));
+        assert_eq!(files.len(), low.len());
 
-        for _ in 0..num_segments.max(1) {
-            comparison.extend(self.generate_sine_wave(432.0, segment_duration));
-            comparison.extend(self.generate_sine_wave(440.0, segment_duration));
+        gen.generate_panorama(Category::Rife).unwrap();
+        assert_eq!(gen.skipped_frequencies().len(), 2 * high.len());
+    }
+
+    #[test]
+    fn retuned_variant_scales_by_the_reference_ratio() {
+        let gen = test_support::generator("retune", 0.05);
+        gen.generate_retuned_category(Category::Solfeggio, &[440.0, 432.0], 440.0)
+            .unwrap();
+
+        let dir = gen
+            .output_dir
+            .join(format!("{}_retuned", Category::Solfeggio.dir_name()));
+        let frequencies = |variant: &str| -> Vec<f64> {
+            test_support::wav_files(&dir.join(variant))
+                .iter()
+                .map(|path| {
+                    let stem = path.file_stem().unwrap().to_string_lossy().into_owned();
+                    let hz = stem.rsplit('_').next().unwrap();
+                    hz.trim_end_matches("hz").parse().unwrap()
+                })
+                .collect()
+        };
+
+        let (standard, retuned) = (frequencies("a440"), frequencies("a432"));
+        assert!(!standard.is_empty());
+        assert_eq!(standard.len(), retuned.len());
+        for (a440, a432) in standard.iter().zip(&retuned) {
+            assert!(
+                (a440 * 432.0 / 440.0 - a432).abs() < 0.01,
+                "{} vs {}",
+                a440,
+                a432
+            );
         }
+    }
 
-        self.save_mono_wav(&dir.join("tuning_432_440_comparison.wav"), &comparison)
+    #[test]
+    fn morph_starts_on_the_first_waveform_and_ends_on_the_second() {
+        let gen = test_support::generator("morph", 1.0);
+        let sine = gen.generate_waveform(Waveform::Sine, 220.0, 1.0);
+        let saw = gen.generate_waveform(Waveform::Saw, 220.0, 1.0);
+        let morph = gen.generate_morph(220.0, Waveform::Sine, Waveform::Saw, 1.0);
+
+        let last = morph.len() - 1;
+        assert_eq!(morph[0], sine[0]);
+        assert_eq!(morph[last], saw[last]);
+        // Just in from each end the mix is still almost entirely one waveform
+        assert!((morph[100] - sine[100]).abs() < 0.01);
+        assert!((morph[last - 100] - saw[last - 100]).abs() < 0.01);
+        assert!((morph[last / 2] - 0.5 * (sine[last / 2] + saw[last / 2])).abs() < 1e-3);
     }
 
-    /// Generate Om tone
-    pub fn generate_om(&self) -> Result<(), hound::Error> {
-        fs::create_dir_all(&self.output_dir).ok();
+    #[test]
+    fn room_pink_noise_measures_the_requested_level() {
+        let gen = test_support::generator("room-pink", 2.0);
+        gen.generate_room_pink_file(-20.0).unwrap();
 
-        println!("\n=== Generating Om Tone (136.1 Hz with harmonics) ===");
-        let samples = self.generate_om_tone(self.duration);
-        self.save_mono_wav(&self.output_dir.join("om_136.1hz.wav"), &samples)
+        let (_, _, samples) =
+            test_support::read(&gen.output_dir.join("noise/room_pink_-20dbfs.wav"));
+        let measured = 20.0 * analysis::rms(&samples).log10();
+        assert!((measured + 20.0).abs() < 0.1, "{} dBFS", measured);
+
+        assert!(gen.generate_room_pink_file(0.0).is_err());
     }
 
-    /// Generate noise backgrounds
-    pub fn generate_noise_set(&self) -> Result<(), hound::Error> {
-        let dir = self.output_dir.join("noise");
-        fs::create_dir_all(&dir).ok();
+    #[test]
+    fn also_format_writes_an_encoded_copy() {
+        if !encode::ffmpeg_available() {
+            eprintln!("skipping: ffmpeg not found");
+            return;
+        }
 
-        println!("\n=== Generating Noise Backgrounds ===");
+        let mut gen = test_support::generator("also-format", 0.25);
+        gen.also_formats = vec![OutputFormat::Flac];
+        gen.generate_custom(528.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ)
+            .unwrap();
 
-        println!("  White noise (all frequencies equal)");
-        self.save_mono_wav(
-            &dir.join("white_noise.wav"),
-            &self.generate_white_noise(self.duration),
-        )?;
+        let wav = gen.output_dir.join("custom_528.00hz_sine.wav");
+        assert!(wav.exists());
+        assert!(wav.with_extension("flac").exists());
+    }
 
-        println!("  Pink noise (1/f, nature-like)");
-        self.save_mono_wav(
-            &dir.join("pink_noise.wav"),
-            &self.generate_pink_noise(self.duration),
-        )?;
+    #[test]
+    fn pulse_rate_follows_tempo_and_subdivision() {
+        let gen = test_support::generator("pulse", 4.0);
+        let pulses = |bpm: f64, subdivision: u32| {
+            gen.generate_pulse_file(bpm, subdivision, 200.0).unwrap();
+            let name = format!("pulse_{}bpm_x{}_200hz.wav", bpm, subdivision);
+            let (_, rate, samples) = test_support::read(&gen.output_dir.join(name));
+            test_support::onsets(&samples, rate as usize / 100, 0.4)
+        };
 
-        println!("  Brown noise (1/f², deep rumble)");
-        self.save_mono_wav(
-            &dir.join("brown_noise.wav"),
-            &self.generate_brown_noise(self.duration),
-        )
+        // Four seconds at 1 Hz and 2 Hz
+        assert_eq!(pulses(60.0, 1), 4);
+        assert_eq!(pulses(60.0, 2), 8);
+        assert_eq!(pulses(120.0, 1), 8);
     }
 
-    /// Generate a frequency sweep file
-    pub fn generate_frequency_sweep_file(&self, start: f64, end: f64) -> Result<(), hound::Error> {
-        fs::create_dir_all(&self.output_dir).ok();
+    #[test]
+    fn fractional_noise_slope_steepens_with_hurst() {
+        let gen = test_support::generator("fnoise", 3.0);
+        let rate = gen.config.sample_rate;
 
-        println!(
-            "\n=== Generating Frequency Sweep: {} Hz to {} Hz ===",
-            start, end
+        // Least-squares slope of log power density against log frequency
+        let slope = |hurst: f64| {
+            let noise = gen.generate_fractional_noise(hurst, 3.0);
+            let points: Vec<(f64, f64)> = analysis::band_energies(&noise, rate, 50.0, 16)
+                .into_iter()
+                .filter(|&(_, hi, _)| hi < 8000.0)
+                .map(|(lo, hi, energy)| ((lo * hi).sqrt().log10(), (energy / (hi - lo)).log10()))
+                .collect();
+            let n = points.len() as f64;
+            let (mx, my) = points
+                .iter()
+                .fold((0.0, 0.0), |(sx, sy), &(x, y)| (sx + x / n, sy + y / n));
+            let (sxy, sxx) = points.iter().fold((0.0, 0.0), |(sxy, sxx), &(x, y)| {
+                (sxy + (x - mx) * (y - my), sxx + (x - mx) * (x - mx))
+            });
+            sxy / sxx
+        };
+
+        let slopes: Vec<f64> = [0.2, 0.5, 0.8].iter().map(|&h| slope(h)).collect();
+        assert!(
+            slopes[0] > slopes[1] && slopes[1] > slopes[2],
+            "{:?}",
+            slopes
         );
-        let samples = self.generate_frequency_sweep(start, end, self.duration);
-        let filename = format!("sweep_{:.0}hz_to_{:.0}hz.wav", start, end);
-        self.save_mono_wav(&self.output_dir.join(filename), &samples)
     }
 
-    /// Generate a drone file from multiple frequencies
-    pub fn generate_drone_file(&self, frequencies: &[f64]) -> Result<(), hound::Error> {
-        fs::create_dir_all(&self.output_dir).ok();
+    #[test]
+    fn centered_signal_lands_entirely_in_mid() {
+        let mut gen = test_support::generator("ms-split", 0.5);
+        gen.ms_split = true;
+        let tone = gen.generate_sine_wave(300.0, 0.5);
+        let frames: Vec<[f64; 2]> = tone.iter().map(|&s| [0.5 * s, 0.5 * s]).collect();
+        gen.save_stereo_wav(&gen.output_dir.join("centered.wav"), &frames)
+            .unwrap();
 
-        let freq_str: Vec<String> = frequencies.iter().map(|f| format!("{:.0}", f)).collect();
-        println!("\n=== Generating Drone: {} Hz ===", freq_str.join(", "));
+        let (_, _, mid) = test_support::read(&gen.output_dir.join("centered_mid.wav"));
+        let (_, _, side) = test_support::read(&gen.output_dir.join("centered_side.wav"));
+        let expected = analysis::rms(&tone) * 0.5 * std::f64::consts::SQRT_2;
+        assert!((analysis::rms(&mid) - expected).abs() < 1e-3);
+        assert!(analysis::rms(&side) < 1e-4);
+        assert!(!gen.output_dir.join("centered.wav").exists());
+    }
 
-        let samples = self.generate_drone(frequencies, self.duration);
-        let filename = format!("drone_{}.wav", freq_str.join("_"));
-        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+    #[test]
+    fn noise_entrainment_pulses_at_the_beat() {
+        let gen = test_support::generator("noise-entrainment", 3.0);
+        gen.generate_noise_entrainment_file(NoiseColor::Pink, 10.0)
+            .unwrap();
+
+        let path = gen.output_dir.join("noise/noise_entrainment_pink_10hz.wav");
+        let (_, rate, samples) = test_support::read(&path);
+        let measured = analysis::modulation_frequency(&samples, rate, 5.0, 20.0);
+        assert!((measured - 10.0).abs() < 0.25, "{} Hz", measured);
     }
 
-    /// Generate a singing bowl tone
-    pub fn generate_bowl_file(&self, frequency: f64) -> Result<(), hound::Error> {
-        fs::create_dir_all(&self.output_dir).ok();
+    #[test]
+    fn resume_skips_complete_files_and_redoes_stale_ones() {
+        let fixture = FrequencyInfo {
+            hz: 528.0,
+            name: "resume",
+            description: "test fixture",
+        };
+        let mut gen = test_support::generator("resume", 0.25);
+        let dir = gen.output_dir.clone();
+        gen.generate_frequency_file(&dir, "test", &fixture, false)
+            .unwrap();
+        let path = test_support::wav_files(&dir).remove(0);
 
-        println!("\n=== Generating Singing Bowl: {} Hz ===", frequency);
-        let samples = self.generate_singing_bowl(frequency, self.duration);
-        let filename = format!("bowl_{:.0}hz.wav", frequency);
-        self.save_mono_wav(&self.output_dir.join(filename), &samples)
+        // A silent stand-in of the right format and length should be left alone
+        let frames = gen.sample_count(0.25);
+        gen.save_mono_wav(&path, &vec![0.0; frames]).unwrap();
+        gen.resume = true;
+        let tone = gen
+            .generate_frequency_file(&dir, "test", &fixture, false)
+            .unwrap();
+        assert!(tone.is_none());
+        assert_eq!(analysis::rms(&test_support::read(&path).2), 0.0);
+
+        // `--both` still gets the tone for its master without touching the file
+        gen.both = true;
+        let tone = gen
+            .generate_frequency_file(&dir, "test", &fixture, false)
+            .unwrap();
+        assert_eq!(tone.map(|tone| tone.len()), Some(frames));
+        assert_eq!(analysis::rms(&test_support::read(&path).2), 0.0);
+        gen.both = false;
+
+        // A different bit depth no longer matches, so the file is rendered again
+        gen.config.bit_depth = 24;
+        gen.generate_frequency_file(&dir, "test", &fixture, false)
+            .unwrap();
+        assert_eq!(WavReader::open(&path).unwrap().spec().bits_per_sample, 24);
+        assert!(analysis::rms(&test_support::read(&path).2) > 0.1);
     }
 
-    /// Generate a custom frequency with specified mode
-    pub fn generate_custom(
-        &self,
-        frequency: f64,
-        mode: &GenerationMode,
-    ) -> Result<(), hound::Error> {
-        fs::create_dir_all(&self.output_dir).ok();
+    #[test]
+    fn resume_counts_the_beep_prefix_in_the_expected_length() {
+        let fixture = FrequencyInfo {
+            hz: 528.0,
+            name: "resume",
+            description: "test fixture",
+        };
+        let mut gen = test_support::generator("resume-beeps", 0.25);
+        gen.beep_prefix = true;
+        let dir = gen.output_dir.clone();
+        gen.generate_frequency_file(&dir, "test", &fixture, false)
+            .unwrap();
+        let path = test_support::wav_files(&dir).remove(0);
+        let frames = test_support::read(&path).2.len();
+        assert_eq!(frames, gen.sample_count(0.25) + gen.beep_prefix_len(528.0));
 
-        println!("\n=== Generating Custom {} Hz ({:?}) ===", frequency, mode);
+        gen.save_mono_wav(&path, &vec![0.0; frames]).unwrap();
+        gen.resume = true;
+        gen.generate_frequency_file(&dir, "test", &fixture, false)
+            .unwrap();
+        assert_eq!(analysis::rms(&test_support::read(&path).2), 0.0);
+    }
 
-        match mode {
-            GenerationMode::Sine => {
-                let samples = self.generate_sine_wave(frequency, self.duration);
-                let path = self
-                    .output_dir
-                    .join(format!("custom_{:.2}hz_sine.wav", frequency));
-                self.save_mono_wav(&path, &samples)
-            }
-            GenerationMode::Binaural => {
-                let samples = self.generate_binaural_beat(200.0, frequency, self.duration);
-                let path = self
-                    .output_dir
-                    .join(format!("custom_{:.2}hz_binaural.wav", frequency));
-                self.save_stereo_wav(&path, &samples)
-            }
-            GenerationMode::Isochronic => {
-                let samples = self.generate_isochronic_tone(200.0, frequency, self.duration);
-                let path = self
-                    .output_dir
-                    .join(format!("custom_{:.2}hz_isochronic.wav", frequency));
-                self.save_mono_wav(&path, &samples)
-            }
+    #[test]
+    fn resume_checks_each_mid_side_file() {
+        let fixture = FrequencyInfo {
+            hz: 528.0,
+            name: "resume",
+            description: "test fixture",
+        };
+        let mut gen = test_support::generator("resume-ms", 0.25);
+        gen.ms_split = true;
+        gen.haas = Some(10.0);
+        gen.resume = true;
+        let dir = gen.output_dir.clone();
+        gen.generate_frequency_file(&dir, "test", &fixture, false)
+            .unwrap();
+
+        let files = test_support::wav_files(&dir);
+        assert_eq!(files.len(), 2);
+        let side = files
+            .iter()
+            .find(|f| f.to_string_lossy().ends_with("_side.wav"));
+        fs::remove_file(side.unwrap()).unwrap();
+
+        gen.generate_frequency_file(&dir, "test", &fixture, false)
+            .unwrap();
+        assert_eq!(test_support::wav_files(&dir), files);
+    }
+
+    #[test]
+    fn inharmonicity_stretches_upper_partials_sharp() {
+        let gen = test_support::generator("inharmonicity", 0.1);
+
+        let exact = gen.harmonic_series(100.0, 7, 0.0);
+        for (n, &(freq, weight)) in exact.iter().enumerate() {
+            assert_eq!(freq, 100.0 * (n + 1) as f64);
+            assert_eq!(weight, 1.0 / (n + 1) as f64);
         }
+
+        let stretched = gen.harmonic_series(100.0, 7, 0.001);
+        let cents: Vec<f64> = stretched
+            .iter()
+            .zip(&exact)
+            .map(|(&(sharp, _), &(pure, _))| 1200.0 * (sharp / pure).log2())
+            .collect();
+        assert!(cents.iter().all(|&c| c > 0.0));
+        assert!(
+            cents.windows(2).all(|pair| pair[1] > pair[0]),
+            "{:?}",
+            cents
+        );
     }
-}
 
-/// Generation mode for custom frequencies
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
-pub enum GenerationMode {
-    Sine,
-    Binaural,
-    Isochronic,
-}
+    #[test]
+    fn solfeggio_and_chakras_share_seven_frequencies() {
+        let hz = |list: &[&FrequencyInfo]| list.iter().map(|f| f.hz).collect::<Vec<f64>>();
+        let (shared, only_solfeggio, only_chakras) =
+            compare_categories(Category::Solfeggio, Category::Chakras);
 
-/// Compute fade envelope for sample at index i
-fn compute_fade_envelope(i: usize, num_samples: usize, fade_samples: usize) -> f64 {
-    if i < fade_samples {
-        i as f64 / fade_samples as f64
-    } else if i >= num_samples - fade_samples {
-        (num_samples - i) as f64 / fade_samples as f64
-    } else {
-        1.0
+        assert_eq!(
+            hz(&shared),
+            [396.0, 417.0, 528.0, 639.0, 741.0, 852.0, 963.0]
+        );
+        assert_eq!(hz(&only_solfeggio), [174.0, 285.0]);
+        assert!(only_chakras.is_empty());
     }
-}
 
-/// Convert f64 sample to i16
-fn convert_sample_i16(sample: f64) -> i16 {
-    (sample.clamp(-1.0, 1.0) * i16::MAX as f64) as i16
-}
+    #[test]
+    fn envelope_from_constant_reference_keeps_level_and_ramp_imposes_ramp() {
+        let gen = test_support::generator("envelope-from", 2.0);
+        let rate = gen.config.sample_rate as f64;
+        let reference = |name: &str, gain: fn(f64) -> f64| {
+            let samples: Vec<f64> = (0..2 * gen.config.sample_rate)
+                .map(|i| {
+                    let t = i as f64 / rate;
+                    0.5 * gain(t) * (2.0 * PI * 1000.0 * t).sin()
+                })
+                .collect();
+            let path = gen.output_dir.join(name);
+            gen.write_wav(&path, 1, &samples).unwrap();
+            gen.load_amplitude_envelope(&path).unwrap()
+        };
+        let shaped = |envelope: &[f64]| {
+            let mut tone = gen.generate_sine_wave(200.0, 2.0);
+            gen.apply_amplitude_envelope(&mut tone, envelope);
+            let half = tone.len() / 2;
+            // Skip the follower's attack at the very start
+            let early = analysis::rms(&tone[half / 4..half]);
+            let late = analysis::rms(&tone[half..]);
+            late / early
+        };
 
-/// Convert f64 sample to i32 (24-bit)
-fn convert_sample_i32_24bit(sample: f64) -> i32 {
-    (sample.clamp(-1.0, 1.0) * 8388607.0) as i32
-}
+        let constant = reference("constant.wav", |_| 1.0);
+        assert!((shaped(&constant) - 1.0).abs() < 0.05);
 
-/// Convert f64 sample to i32
-fn convert_sample_i32(sample: f64) -> i32 {
-    (sample.clamp(-1.0, 1.0) * i32::MAX as f64) as i32
-}
+        let ramp = reference("ramp.wav", |t| t / 2.0);
+        assert!(shaped(&ramp) > 2.0);
+    }
 
-/// Write mono samples to WAV writer based on bit depth
-fn write_samples<W: std::io::Write + std::io::Seek>(
-    writer: &mut WavWriter<W>,
-    samples: &[f64],
-    bit_depth: u16,
-) -> Result<(), hound::Error> {
-    match bit_depth {
-        16 => {
-            for &sample in samples {
-                writer.write_sample(convert_sample_i16(sample))?;
-            }
+    #[test]
+    fn max_file_size_rejects_oversized_output_and_allows_small() {
+        let mut gen = test_support::generator("max-file-size", 1.0);
+        // One second of 16-bit mono at 44.1 kHz is about 88 kB
+        gen.max_file_bytes = Some(50_000);
+        let result = gen.generate_custom(100.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ);
+        assert!(matches!(result, Err(SpiritError::InvalidInput(_))));
+        assert!(test_support::wav_files(&gen.output_dir).is_empty());
+
+        gen.max_file_bytes = Some(100_000);
+        gen.generate_custom(100.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ)
+            .unwrap();
+        assert_eq!(test_support::wav_files(&gen.output_dir).len(), 1);
+    }
+
+    #[test]
+    fn impulse_test_signal_has_one_nonzero_sample_at_the_midpoint() {
+        let gen = test_support::generator("impulse", 1.0);
+        gen.generate_test_signal_file(TestSignal::Impulse, 20.0, 20000.0)
+            .unwrap();
+
+        let (channels, _, samples) = test_support::read(&gen.output_dir.join("test_impulse.wav"));
+        assert_eq!(channels, 1);
+        let nonzero: Vec<usize> = (0..samples.len()).filter(|&i| samples[i] != 0.0).collect();
+        assert_eq!(nonzero, [samples.len() / 2]);
+        assert!(samples[samples.len() / 2] > 0.99);
+    }
+
+    #[test]
+    fn antialias_lowers_folded_harmonics_of_the_10khz_rife_tone() {
+        // Aliased energy between the 10 kHz tone and its 20 kHz second harmonic,
+        // where the folded third harmonic (44.1 - 30 = 14.1 kHz) lands
+        let aliasing = |antialias: bool| {
+            let mut gen = test_support::generator("antialias", 0.5);
+            gen.effects = vec![effects::Effect::Saturation(4.0)];
+            gen.antialias = antialias;
+            gen.generate_category(Category::Rife).unwrap();
+
+            let dir = gen.output_dir.join(Category::Rife.dir_name());
+            let path = test_support::wav_files(&dir)
+                .into_iter()
+                .find(|path| path.to_string_lossy().contains("10000"))
+                .unwrap();
+            let (_, rate, samples) = test_support::read(&path);
+            let spectrum = analysis::magnitude_spectrum(&samples);
+            let bin_hz = rate as f64 / ((spectrum.len() - 1) * 2) as f64;
+            let bins = (11_000.0 / bin_hz) as usize..(19_000.0 / bin_hz) as usize;
+            spectrum[bins].iter().map(|m| m * m).sum::<f64>()
+        };
+
+        assert!(aliasing(true) < aliasing(false) / 10.0);
+    }
+
+    #[test]
+    fn multi_beat_side_channel_holds_each_beat_partner() {
+        // 32768 samples at 8192 Hz gives quarter-hertz bins
+        let config = AudioConfig {
+            sample_rate: 8192,
+            ..AudioConfig::default()
+        };
+        let gen = AudioGenerator::new(test_support::temp_dir("multi-beat"), 4.0, config);
+        let side: Vec<f64> = gen
+            .generate_multi_beat(200.0, &[4.0, 7.0, 10.0], 4.0)
+            .into_iter()
+            .map(|frame| to_mid_side(frame)[1])
+            .collect();
+        let spectrum = analysis::magnitude_spectrum(&side);
+        let magnitude = |hz: f64| spectrum[(hz * 4.0).round() as usize];
+
+        // Away from any partner, e.g. halfway between the beats
+        let floor = magnitude(202.0).max(magnitude(205.5)).max(magnitude(208.5));
+        for beat in [4.0, 7.0, 10.0] {
+            assert!(magnitude(200.0 + beat) > 10.0 * floor);
         }
-        24 => {
-            for &sample in samples {
-                writer.write_sample(convert_sample_i32_24bit(sample))?;
-            }
+    }
+
+    #[test]
+    fn swap_channels_writes_the_source_right_channel_on_the_left() {
+        let mut gen = test_support::generator("swap", 0.1);
+        gen.swap_channels = true;
+        let left = gen.generate_sine_wave(300.0, 0.1);
+        let right = gen.generate_sine_wave(700.0, 0.1);
+        let frames: Vec<[f64; 2]> = left.iter().zip(&right).map(|(&l, &r)| [l, r]).collect();
+        let path = gen.output_dir.join("swapped.wav");
+        gen.save_stereo_wav(&path, &frames).unwrap();
+
+        let (channels, _, samples) = test_support::read(&path);
+        let written = [
+            test_support::channel(&samples, channels, 0),
+            test_support::channel(&samples, channels, 1),
+        ];
+        for (written, source) in written.iter().zip([&right, &left]) {
+            assert_eq!(written.len(), source.len());
+            assert!(written
+                .iter()
+                .zip(source)
+                .all(|(w, s)| (w - s).abs() < 1e-3));
         }
-        _ => {
-            for &sample in samples {
-                writer.write_sample(convert_sample_i32(sample))?;
-            }
+    }
+
+    #[test]
+    fn comb_tones_are_log_spaced_within_range() {
+        let tones = AudioGenerator::log_spaced(100.0, 10_000.0, 20);
+        assert_eq!(tones.len(), 20);
+        assert!((tones[0] - 100.0).abs() < 1e-9);
+        assert!((tones[19] - 10_000.0).abs() < 1e-6);
+
+        let ratio = tones[1] / tones[0];
+        assert!(ratio > 1.0);
+        for pair in tones.windows(2) {
+            assert!((pair[1] / pair[0] - ratio).abs() < 1e-9);
         }
     }
-    Ok(())
-}
 
-/// Write stereo samples to WAV writer based on bit depth
-fn write_stereo_samples<W: std::io::Write + std::io::Seek>(
-    writer: &mut WavWriter<W>,
-    samples: &[[f64; 2]],
-    bit_depth: u16,
-) -> Result<(), hound::Error> {
-    match bit_depth {
-        16 => {
-            for &[left, right] in samples {
-                writer.write_sample(convert_sample_i16(left))?;
-                writer.write_sample(convert_sample_i16(right))?;
-            }
+    #[test]
+    fn sleep_descent_beat_falls_from_ten_to_two_hz_over_noise() {
+        const SECS: f64 = 600.0;
+        const WINDOW: usize = 8192;
+        let config = AudioConfig {
+            sample_rate: 2000,
+            ..AudioConfig::default()
+        };
+        let gen = AudioGenerator::new(test_support::temp_dir("sleep-descent"), SECS, config);
+        gen.generate_sleep_descent_file(200.0, 0.3).unwrap();
+
+        let dir = gen.output_dir.join("binaural");
+        let (channels, rate, samples) = test_support::read(&test_support::wav_files(&dir)[0]);
+        let left = test_support::channel(&samples, channels, 0);
+        let right = test_support::channel(&samples, channels, 1);
+        // Over a 4 s window the beat moves by well under the tolerance
+        let beat = |start: usize| {
+            let peak = |channel: &[f64]| {
+                analysis::dominant_frequency(&channel[start..start + WINDOW], rate, 150.0, 250.0)
+            };
+            peak(&right) - peak(&left)
+        };
+        assert!((beat(0) - 10.0).abs() < 0.2);
+        assert!((beat(left.len() - WINDOW) - 2.0).abs() < 0.2);
+
+        // The tones sit near 200 Hz, so anything well above them is the noise bed
+        let bed = |noise_level: f64| {
+            let gen = AudioGenerator::new(test_support::temp_dir("sleep-bed"), 20.0, config);
+            gen.generate_sleep_descent_file(200.0, noise_level).unwrap();
+            let dir = gen.output_dir.join("binaural");
+            let (channels, rate, samples) = test_support::read(&test_support::wav_files(&dir)[0]);
+            let left = test_support::channel(&samples, channels, 0);
+            let middle = left.len() / 2;
+            analysis::band_energies(&left[middle..middle + WINDOW], rate, 20.0, 8)
+                .iter()
+                .filter(|(lo, _, _)| *lo > 400.0)
+                .map(|band| band.2)
+                .sum::<f64>()
+        };
+        assert!(bed(0.3) > 100.0 * bed(0.0));
+    }
+
+    #[test]
+    fn force_mono_binaural_files_have_one_channel() {
+        let mut gen = test_support::generator("force-mono", 0.05);
+        gen.force_mono = true;
+        gen.generate_binaural_set(200.0, BandEdge::Mid).unwrap();
+
+        let files = test_support::wav_files(&gen.output_dir.join("binaural"));
+        assert_eq!(files.len(), BRAINWAVE_STATES.len());
+        for file in files {
+            assert_eq!(test_support::read(&file).0, 1);
+        }
+    }
+
+    #[test]
+    fn both_writes_individual_files_and_the_master() {
+        let mut gen = test_support::generator("both", 0.1);
+        gen.both = true;
+        gen.generate_category(Category::Chakras).unwrap();
+
+        let dir = gen.output_dir.join(Category::Chakras.dir_name());
+        let master = dir.join(format!("{}_sequence.wav", Category::Chakras.dir_name()));
+        let (individual, masters): (Vec<PathBuf>, Vec<PathBuf>) = test_support::wav_files(&dir)
+            .into_iter()
+            .partition(|path| *path != master);
+        assert_eq!(masters, std::slice::from_ref(&master));
+        assert_eq!(individual.len(), Category::Chakras.frequencies().len());
+
+        let individual_len: usize = individual
+            .iter()
+            .map(|path| test_support::read(path).2.len())
+            .sum();
+        assert_eq!(test_support::read(&master).2.len(), individual_len);
+    }
+
+    #[test]
+    fn duplicated_custom_tone_fills_every_channel() {
+        let mut gen = test_support::generator("duplicate", 0.1);
+        gen.generate_custom_duplicated(200.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ, 6)
+            .unwrap();
+        let path = gen.output_dir.join("custom_200.00hz_sine_6ch.wav");
+        let (channels, _, samples) = test_support::read(&path);
+        assert_eq!(channels, 6);
+        assert!(samples.iter().any(|&s| s != 0.0));
+        for frame in samples.chunks(6) {
+            assert!(frame.iter().all(|&s| s == frame[0]));
+        }
+
+        // Two channels go through the stereo path, so balance still applies
+        gen.balance = 1.0;
+        gen.generate_custom_duplicated(200.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ, 2)
+            .unwrap();
+        let path = gen.output_dir.join("custom_200.00hz_sine_2ch.wav");
+        let (channels, _, samples) = test_support::read(&path);
+        assert_eq!(channels, 2);
+        assert_eq!(analysis::rms(&test_support::channel(&samples, 2, 0)), 0.0);
+        assert!(analysis::rms(&test_support::channel(&samples, 2, 1)) > 0.1);
+    }
+
+    #[test]
+    fn auto_carrier_is_a_deterministic_note_near_200_hz() {
+        let gen = test_support::generator("auto-carrier", 0.1);
+
+        let carrier = gen.pick_carrier(10.0, None, true);
+        assert!((carrier - 196.0).abs() < 0.01);
+        assert_eq!(gen.pick_carrier(10.0, None, true), carrier);
+
+        // A 50 Hz beat needs at least 500 Hz; B4 is too low, so C5
+        assert!((gen.pick_carrier(50.0, None, true) - 523.25).abs() < 0.01);
+
+        assert_eq!(gen.pick_carrier(10.0, None, false), DEFAULT_CARRIER_HZ);
+        assert_eq!(gen.pick_carrier(10.0, Some(150.0), true), 150.0);
+    }
+
+    #[test]
+    fn exact_duration_writes_the_rounded_sample_count() {
+        // Each of these lands just under (or past the middle of) a whole sample at 44.1 kHz
+        for duration in [0.57, 0.7, 1.13, 2.3, 1.00002] {
+            let mut gen = test_support::generator("exact-duration", duration);
+            gen.exact_duration = true;
+            gen.generate_custom(100.0, &GenerationMode::Sine, DEFAULT_CARRIER_HZ)
+                .unwrap();
+
+            let (_, rate, samples) =
+                test_support::read(&test_support::wav_files(&gen.output_dir)[0]);
+            let expected = (rate as f64 * duration).round() as usize;
+            assert_eq!(samples.len(), expected, "{} s", duration);
+            assert_ne!(expected, (rate as f64 * duration) as usize);
         }
-        24 => {
-            for &[left, right] in samples {
-                writer.write_sample(convert_sample_i32_24bit(left))?;
-                writer.write_sample(convert_sample_i32_24bit(right))?;
+    }
+
+    #[test]
+    fn default_filenames_parse_back_to_their_entries() {
+        let gen = test_support::generator("parse-name", 0.1);
+        for &category in Category::all() {
+            for freq_info in category.frequencies().iter().filter(|f| f.hz > 0.0) {
+                let filename = format!(
+                    "{}.wav",
+                    gen.frequency_filename(category.file_prefix(), freq_info)
+                );
+                let (parsed_category, parsed) = parse_frequency_filename(&filename).unwrap();
+                assert_eq!(parsed_category, category, "{}", filename);
+                assert_eq!(parsed.name, freq_info.name);
+                assert_eq!(parsed.hz, freq_info.hz);
             }
         }
-        _ => {
-            for &[left, right] in samples {
-                writer.write_sample(convert_sample_i32(left))?;
-                writer.write_sample(convert_sample_i32(right))?;
+        assert!(parse_frequency_filename("solfeggio_528_529.00hz.wav").is_none());
+    }
+
+    #[test]
+    fn comb_with_crest_factor_target_lands_near_it() {
+        for target in [6.0, 9.0] {
+            let mut gen = test_support::generator("crest-target", 1.0);
+            gen.crest_factor = Some(target);
+            gen.generate_comb_file(100.0, 5000.0, 12).unwrap();
+
+            let (_, _, samples) = test_support::read(&test_support::wav_files(&gen.output_dir)[0]);
+            let measured = analysis::crest_factor_db(&samples);
+            assert!(
+                (measured - target).abs() < 0.3,
+                "{} dB for {} dB",
+                measured,
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn sonify_writes_one_segment_per_digit() {
+        let mut gen = test_support::generator("sonify", 1.0);
+        gen.cue_sheet = true;
+        gen.generate_sonify_file("3.14159\n", 220.0, 20.0, 0.25)
+            .unwrap();
+
+        let path = gen.output_dir.join("sonify_314159.wav");
+        let sheet = fs::read_to_string(path.with_extension("cue")).unwrap();
+        assert_eq!(sheet.matches("TRACK").count(), 6);
+        let (_, rate, samples) = test_support::read(&path);
+        assert_eq!(samples.len(), 6 * gen.sample_count(0.25));
+        assert_eq!(rate, gen.config.sample_rate);
+    }
+
+    #[test]
+    fn width_zero_writes_mono_and_wider_raises_the_side_share() {
+        // Side-to-mid RMS ratio of the written file
+        let side_share = |width: f64| {
+            let mut gen = test_support::generator("width", 0.5);
+            gen.width = width;
+            let frames = gen.generate_binaural_beat(200.0, 10.0, 0.5);
+            let path = gen.output_dir.join("width.wav");
+            gen.save_stereo_wav(&path, &frames).unwrap();
+
+            let (channels, _, samples) = test_support::read(&path);
+            let left = test_support::channel(&samples, channels, 0);
+            let right = test_support::channel(&samples, channels, 1);
+            if width == 0.0 {
+                assert_eq!(left, right);
             }
+            let (mid, side): (Vec<f64>, Vec<f64>) = left
+                .iter()
+                .zip(&right)
+                .map(|(&l, &r)| (l + r, l - r))
+                .unzip();
+            analysis::rms(&side) / analysis::rms(&mid)
+        };
+
+        assert_eq!(side_share(0.0), 0.0);
+        assert!(side_share(1.8) > 1.5 * side_share(1.0));
+    }
+
+    #[test]
+    fn om_binaural_keeps_the_fundamental_and_offsets_the_right_channel() {
+        let config = AudioConfig {
+            sample_rate: 8192,
+            ..AudioConfig::default()
+        };
+        let gen = AudioGenerator::new(test_support::temp_dir("om-binaural"), 4.0, config);
+        gen.generate_om_binaural_file(7.83).unwrap();
+
+        let dir = gen.output_dir.join("binaural");
+        let (channels, rate, samples) = test_support::read(&test_support::wav_files(&dir)[0]);
+        let left = test_support::channel(&samples, channels, 0);
+        let right = test_support::channel(&samples, channels, 1);
+        let peak = |channel: &[f64], low: f64, high: f64| {
+            analysis::dominant_frequency(channel, rate, low, high)
+        };
+
+        assert!((peak(&left, 100.0, 200.0) - OM_HZ).abs() < 0.1);
+        // Every partial is offset by the beat, so the harmonics beat at the same rate
+        for (low, high) in [(100.0, 200.0), (250.0, 300.0)] {
+            let beat = peak(&right, low, high) - peak(&left, low, high);
+            assert!((beat - 7.83).abs() < 0.1, "{} Hz beat", beat);
         }
     }
-    Ok(())
 }
@@ -1,26 +1,263 @@
 //! Spirit - Generate frequency-based audio files for meditation and exploration.
 
+mod analysis;
+mod archive;
+mod batch;
+mod checksum;
 mod cli;
 mod config;
+mod effects;
+mod encode;
+mod error;
 mod frequency;
 mod generator;
+mod oscillator;
+mod resample;
+mod rng;
+#[cfg(test)]
+mod test_support;
+mod wav;
 
-use clap::Parser;
+use std::io::BufRead;
+use std::sync::atomic::Ordering;
 
-use cli::{print_frequency_list, Cli, Commands};
-use config::AudioConfig;
-use frequency::Category;
-use generator::AudioGenerator;
+use clap::{CommandFactory, FromArgMatches, ValueEnum};
+
+use cli::{
+    expand_output_template, print_frequency_info, print_frequency_list, print_modes,
+    print_parsed_name, run_headless_check, Cli, Commands, SampleRate,
+};
+use config::{min_sample_rate_for, AudioConfig, DEFAULT_SAMPLE_RATE};
+use error::SpiritError;
+use frequency::{Category, TuningSystem};
+use generator::{AudioGenerator, BandEdge, GenerationMode, DEFAULT_CARRIER_HZ};
+use wav::{ChannelLayout, Dither};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches)?;
+
+    if cli.float && !matches!(cli.bit_depth, 32 | 64) {
+        return Err("--float requires a bit depth of 32 or 64".into());
+    }
+
+    if (cli.dither || cli.dither_noise_shaping) && (cli.bit_depth != 16 || cli.float) {
+        return Err("--dither applies only to 16-bit integer output".into());
+    }
+
+    if !cli.also_format.is_empty() && !encode::ffmpeg_available() {
+        return Err("--also-format requires ffmpeg on the PATH".into());
+    }
+
+    if !(0.0..=1.0).contains(&cli.duty) {
+        return Err(format!("--duty must be between 0 and 1 (got {})", cli.duty).into());
+    }
+
+    if cli.haas.is_some_and(|ms| !(5.0..=30.0).contains(&ms)) {
+        return Err("--haas must be between 5 and 30 ms".into());
+    }
+
+    if cli.upmix.is_some_and(|w| !(0.0..=1.0).contains(&w)) {
+        return Err("--upmix width must be between 0 and 1".into());
+    }
+
+    if cli
+        .crest_factor
+        .is_some_and(|db| !(0.0..=40.0).contains(&db))
+    {
+        return Err("--crest-factor must be between 0 and 40 dB".into());
+    }
+
+    if !(0.0..=2.0).contains(&cli.width) {
+        return Err(format!("--width must be between 0 and 2 (got {})", cli.width).into());
+    }
+
+    if cli.warmth.is_some_and(|d| d <= 0.0 || !d.is_finite()) {
+        return Err("--warmth drive must be greater than 0".into());
+    }
+    if cli.rotate.is_some_and(|r| r <= 0.0 || !r.is_finite()) {
+        return Err("--rotate must be a positive rate in Hz".into());
+    }
+
+    if cli.delay.is_some_and(|d| d <= 0.0 || d.is_nan()) {
+        return Err("--delay must be greater than 0 seconds".into());
+    }
+
+    if cli
+        .render_silence_between_categories
+        .is_some_and(|gap| !(0.0..=3600.0).contains(&gap))
+    {
+        return Err("--render-silence-between-categories must be 0 to 3600 seconds".into());
+    }
+
+    let sample_rate = match cli.sample_rate {
+        SampleRate::Fixed(rate) => rate,
+        SampleRate::Auto => {
+            // Mixed-in noise is broadband, so it always needs the default rate
+            let max_freq = cli
+                .command
+                .max_frequency()
+                .filter(|_| cli.mix_noise.is_none());
+            let rate = max_freq.map_or(DEFAULT_SAMPLE_RATE, min_sample_rate_for);
+            println!("Auto sample rate: {} Hz", rate);
+            rate
+        }
+    };
 
     let config = AudioConfig {
-        sample_rate: cli.sample_rate,
+        sample_rate,
         bit_depth: cli.bit_depth,
+        float: cli.float || cli.bit_depth == 64,
+    };
+
+    if let Some(limit_mb) = cli.max_file_size.filter(|_| !cli.force) {
+        let channels = if cli.ms_split || (cli.force_mono && cli.command.output_channels() == 2) {
+            1
+        } else if cli.rotate.is_some()
+            || cli.haas.is_some()
+            || cli.upmix.is_some()
+            || cli.delay.is_some()
+        {
+            cli.command.output_channels().max(2)
+        } else {
+            cli.command.output_channels()
+        };
+        let estimate_mb = config.estimated_wav_bytes(cli.duration, channels) as f64 / 1e6;
+        if estimate_mb > limit_mb {
+            return Err(format!(
+                "estimated file size {:.1} MB exceeds --max-file-size {} MB (use --force to write it anyway)",
+                estimate_mb, limit_mb
+            )
+            .into());
+        }
+    }
+
+    let category_name = match cli.command.to_category() {
+        Some(category) => category.dir_name(),
+        None => matches.subcommand_name().unwrap_or("misc"),
     };
+    let output_dir = expand_output_template(&cli.output, category_name);
 
-    let mut gen = AudioGenerator::new(cli.output.clone(), cli.duration, config);
+    let mut gen = AudioGenerator::new(output_dir, cli.duration, config);
+    gen.append = cli.append;
+    gen.resume = cli.resume;
+    gen.max_file_bytes = cli
+        .max_file_size
+        .filter(|_| !cli.force)
+        .map(|mb| (mb * 1e6) as u64);
+    gen.fade_window = cli.fade_window;
+    gen.tukey_flat = cli.tukey_flat;
+    gen.no_fade = cli.no_fade;
+    gen.balance = cli.balance;
+    gen.seed = cli.seed;
+    gen.rng = cli.rng;
+    gen.random_phase = cli.random_phase;
+    gen.target_lufs = cli.target_lufs;
+    gen.mix_noise = cli.mix_noise;
+    gen.noise_level = cli.noise_level;
+    gen.tone_auto_gain = cli.tone_auto_gain;
+    gen.concat = cli.concat;
+    gen.both = cli.both;
+    gen.beep_prefix = cli.beep_prefix;
+    gen.also_formats = cli.also_format.clone();
+    gen.envelope = cli.envelope_preset.map(|preset| preset.adsr());
+    gen.dither = if cli.dither_noise_shaping {
+        Some(Dither::NoiseShaped)
+    } else if cli.dither {
+        Some(Dither::Tpdf)
+    } else {
+        None
+    };
+    gen.category_gap = cli.render_silence_between_categories;
+    gen.match_loudness = cli.match_loudness;
+    gen.cue_sheet = cli.cue_sheet;
+    gen.phase_accumulator = cli.phase_accumulator;
+    gen.exact_duration = cli.exact_duration;
+    gen.crest_factor = cli.crest_factor;
+    gen.preview_spectrum = cli.preview_spectrum;
+    gen.duty = cli.duty;
+    gen.ms_split = cli.ms_split;
+    gen.width = cli.width;
+    gen.swap_channels = cli.swap_channels;
+    gen.force_mono = cli.force_mono;
+    gen.rotate = cli.rotate;
+    gen.upmix = cli.upmix;
+    gen.antialias = cli.antialias;
+    gen.haas = cli.haas;
+    gen.delay = cli.delay;
+    gen.delay_feedback = cli.delay_feedback;
+    gen.delay_mix = cli.delay_mix;
+    gen.tuning = cli.tuning_system.map_or(cli.tuning, TuningSystem::a4_hz);
+    gen.tuning_system = cli.tuning_system;
+    gen.name_template = cli.name_template.clone();
+    if let Some(fx) = &cli.fx {
+        gen.effects = effects::parse_chain(fx, gen.config.sample_rate)?;
+    }
+    if let Some(path) = &cli.envelope_from {
+        gen.amplitude_envelope = Some(gen.load_amplitude_envelope(path)?);
+    }
+    if let Some(drive) = cli.warmth {
+        gen.effects.push(effects::Effect::Saturation(drive));
+    }
+    if let Some(bits) = cli.bitcrush {
+        gen.effects
+            .push(effects::Effect::Bitcrush(bits, cli.bitcrush_hold));
+    }
+
+    // Let the file in progress finish so it is not left truncated
+    let cancel = gen.cancel.clone();
+    ctrlc::set_handler(move || {
+        cancel.store(true, Ordering::SeqCst);
+        eprintln!("\n  Interrupted: finishing the current file...");
+    })?;
+
+    let write_manifest = cli.manifest;
+    let archive_path = cli.archive.clone();
+    let result = run(cli, &mut gen);
+    if gen.is_cancelled() {
+        println!("\nCancelled after saving {} file(s)", gen.saved_files());
+        std::process::exit(130);
+    }
+    result?;
+
+    let skipped = gen.skipped_frequencies();
+    if !skipped.is_empty() {
+        println!(
+            "\n{} frequency(ies) skipped at or above Nyquist ({} Hz):",
+            skipped.len(),
+            gen.config.sample_rate / 2
+        );
+        for (name, hz) in &skipped {
+            println!("  {:.2} Hz: {}", hz, name);
+        }
+    }
+
+    let replaced = gen.replaced_samples();
+    if replaced > 0 {
+        println!(
+            "\n{} non-finite sample(s) were written as silence",
+            replaced
+        );
+    }
+
+    let mut files = gen.written_files();
+    if write_manifest {
+        let path = checksum::write_manifest(&gen.output_dir, &files)?;
+        println!("\nManifest: {} ({} file(s))", path.display(), files.len());
+        files.push(path);
+    }
+    if let Some(path) = archive_path {
+        let count = archive::write_archive(&path, &gen.output_dir, &files)?;
+        println!("\nArchive: {} ({} file(s))", path.display(), count);
+    }
+    Ok(())
+}
+
+/// Dispatch the parsed command
+fn run(cli: Cli, gen: &mut AudioGenerator) -> Result<(), Box<dyn std::error::Error>> {
+    if let Commands::Solfeggio { extended: true } = cli.command {
+        return Ok(gen.generate_solfeggio_extended()?);
+    }
 
     // Handle category-based commands via mapping
     if let Some(category) = cli.command.to_category() {
@@ -30,23 +267,111 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Handle special commands
     match cli.command {
         Commands::List => {
-            print_frequency_list();
+            print_frequency_list(gen.tuning);
+        }
+
+        Commands::InfoFreq { query, json } => {
+            print_frequency_info(&query, json, gen.tuning);
+        }
+
+        Commands::ParseName { filename, json } => {
+            if !print_parsed_name(&filename, json) {
+                return Err(format!(
+                    "'{}' does not match a database entry in the default filename template",
+                    filename
+                )
+                .into());
+            }
+        }
+
+        Commands::Modes => {
+            print_modes();
+        }
+
+        Commands::HeadlessCheck => {
+            let issues = run_headless_check();
+            if issues > 0 {
+                return Err(format!("{} frequency database issue(s) found", issues).into());
+            }
+        }
+
+        Commands::Doctor => {
+            gen.run_doctor();
         }
 
         Commands::All => {
-            generate_all(&mut gen)?;
+            generate_all(gen)?;
+        }
+
+        Commands::Binaural { base, band_edge } => {
+            gen.generate_binaural_set(base, band_edge)?;
+        }
+
+        Commands::Brainwaves { mode } => {
+            gen.generate_brainwave_tones(&mode)?;
+        }
+
+        Commands::BinauralCalibrate { base, start, end } => {
+            gen.generate_binaural_calibration(base, start, end)?;
+        }
+
+        Commands::MultiBinaural { carriers, beat } => {
+            gen.generate_multi_binaural_file(&carriers, beat)?;
+        }
+
+        Commands::SleepDescent { base, noise_level } => {
+            gen.generate_sleep_descent_file(base, noise_level)?;
+        }
+
+        Commands::OmBinaural { beat } => {
+            gen.generate_om_binaural_file(beat)?;
+        }
+
+        Commands::MultiBeat { base, beats } => {
+            gen.generate_multi_beat_file(base, &beats)?;
+        }
+
+        Commands::Panorama { category } => {
+            gen.generate_panorama(category)?;
         }
 
-        Commands::Binaural { base } => {
-            gen.generate_binaural_set(base)?;
+        Commands::Diff {
+            first,
+            second,
+            generate,
+        } => {
+            gen.generate_category_diff(first, second, generate)?;
+        }
+
+        Commands::Retune {
+            category,
+            references,
+            baseline,
+        } => {
+            gen.generate_retuned_category(category, &references, baseline)?;
+        }
+
+        Commands::Near {
+            frequency,
+            tolerance,
+        } => {
+            gen.generate_near(frequency, tolerance)?;
+        }
+
+        Commands::Sampler { each } => {
+            gen.generate_sampler(each)?;
+        }
+
+        Commands::AudioIndex { each } => {
+            gen.generate_audio_index(each)?;
         }
 
         Commands::Schumann => {
             gen.generate_schumann()?;
         }
 
-        Commands::Tuning => {
-            gen.generate_tuning_comparison()?;
+        Commands::Tuning { simultaneous } => {
+            gen.generate_tuning_comparison(simultaneous)?;
         }
 
         Commands::Om => {
@@ -57,31 +382,174 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             gen.generate_noise_set()?;
         }
 
+        Commands::NoiseEntrainment { beat, color } => {
+            gen.generate_noise_entrainment_file(color, beat)?;
+        }
+
+        Commands::Fnoise { hurst } => {
+            gen.generate_fractional_noise_file(hurst)?;
+        }
+
+        Commands::RoomPink { dbfs } => {
+            gen.generate_room_pink_file(dbfs)?;
+        }
+
+        Commands::NoiseBurst { color, on, off } => {
+            gen.generate_noise_burst_file(color, on, off)?;
+        }
+
+        Commands::Convert { input, resampler } => {
+            gen.convert_file(&input, resampler)?;
+        }
+
+        Commands::TestSignal { signal, start, end } => {
+            gen.generate_test_signal_file(signal, start, end)?;
+        }
+
         Commands::Sweep { start, end } => {
             gen.generate_frequency_sweep_file(start, end)?;
         }
 
-        Commands::Drone { frequencies } => {
-            gen.generate_drone_file(&frequencies)?;
+        Commands::Glide { start, end } => {
+            gen.generate_glide_file(start, end)?;
+        }
+
+        Commands::Drone {
+            frequencies,
+            swell,
+            swell_depth,
+            filter_sweep,
+            sweep_min,
+            sweep_max,
+        } => {
+            gen.generate_drone_file(
+                &frequencies,
+                swell.map(|period| (period, swell_depth)),
+                filter_sweep.map(|rate| (sweep_min, sweep_max, rate)),
+            )?;
+        }
+
+        Commands::OctaveDrone { root, octaves } => {
+            gen.generate_octave_drone_file(root, octaves)?;
+        }
+
+        Commands::Note { name } => {
+            gen.generate_note_file(&name)?;
+        }
+
+        Commands::Custom {
+            frequency,
+            mode,
+            carrier,
+            auto_carrier,
+            channels,
+        } => {
+            let carrier = match mode {
+                GenerationMode::Sine => DEFAULT_CARRIER_HZ,
+                _ => gen.pick_carrier(frequency, carrier, auto_carrier),
+            };
+            match channels {
+                Some(channels) => {
+                    gen.generate_custom_duplicated(frequency, &mode, carrier, channels)?
+                }
+                None => gen.generate_custom(frequency, &mode, carrier)?,
+            }
+        }
+
+        Commands::Layer {
+            frequencies,
+            split,
+            layout,
+        } => {
+            let layout = match (split, layout) {
+                (true, _) => ChannelLayout::Stereo,
+                (false, layout) => layout.unwrap_or(ChannelLayout::Mono),
+            };
+            gen.generate_layered_file(&frequencies, layout)?;
+        }
+
+        Commands::Gamma40 { carrier, mode } => {
+            gen.generate_gamma40(carrier, &mode)?;
         }
 
-        Commands::Custom { frequency, mode } => {
-            gen.generate_custom(frequency, &mode)?;
+        Commands::Comb { start, end, count } => {
+            gen.generate_comb_file(start, end, count)?;
         }
 
-        Commands::Layer { frequencies } => {
-            let samples = gen.generate_layered_frequencies(&frequencies, gen.duration);
-            let freq_str: Vec<String> = frequencies.iter().map(|f| format!("{:.0}", f)).collect();
-            println!(
-                "\n=== Generating Layered Frequencies: {} Hz ===",
-                freq_str.join(", ")
-            );
-            let filename = format!("layered_{}.wav", freq_str.join("_"));
-            gen.save_mono_wav(&gen.output_dir.join(filename), &samples)?;
+        Commands::Sonify {
+            sequence,
+            file,
+            base,
+            step,
+            note_length,
+        } => {
+            let sequence = match (sequence, file) {
+                (Some(sequence), _) => sequence,
+                (None, Some(path)) => std::fs::read_to_string(&path)
+                    .map_err(|e| format!("cannot read {}: {}", path.display(), e))?,
+                (None, None) => std::io::read_to_string(std::io::stdin())?,
+            };
+            gen.generate_sonify_file(&sequence, base, step, note_length)?;
         }
 
-        Commands::Bowl { frequency } => {
-            gen.generate_bowl_file(frequency)?;
+        Commands::Harmonics {
+            frequency,
+            count,
+            inharmonicity,
+        } => {
+            gen.generate_harmonics_file(frequency, count, inharmonicity)?;
+        }
+
+        Commands::Wavetable { table, freq } => {
+            gen.generate_wavetable_file(&table, freq)?;
+        }
+
+        Commands::Intermod { f1, f2 } => {
+            gen.generate_intermod_file(f1, f2)?;
+        }
+
+        Commands::Intervals { root, ratios } => {
+            gen.generate_intervals_file(root, &ratios)?;
+        }
+
+        Commands::Pulse {
+            bpm,
+            subdivision,
+            carrier,
+        } => {
+            gen.generate_pulse_file(bpm, subdivision, carrier)?;
+        }
+
+        Commands::Morph {
+            frequency,
+            from,
+            to,
+        } => {
+            gen.generate_morph_file(frequency, from, to)?;
+        }
+
+        Commands::Comma { frequency } => {
+            gen.generate_comma_file(frequency)?;
+        }
+
+        Commands::ZeroBeat {
+            frequency,
+            detune,
+            ramp_to_zero,
+        } => {
+            gen.generate_zero_beat_file(frequency, detune, ramp_to_zero)?;
+        }
+
+        Commands::Batch { manifest } => {
+            batch::run_manifest(gen, &manifest)?;
+        }
+
+        Commands::BatchStdin => {
+            generate_from_lines(gen, std::io::stdin().lock())?;
+        }
+
+        Commands::Bowl { frequency, decay } => {
+            gen.generate_bowl_file(frequency, decay)?;
         }
 
         // Category commands are handled above via to_category()
@@ -92,23 +560,91 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 /// Generate all frequency categories
-fn generate_all(gen: &mut AudioGenerator) -> Result<(), hound::Error> {
+fn generate_all(gen: &mut AudioGenerator) -> Result<(), SpiritError> {
     // Generate all standard categories
-    for category in Category::all() {
-        gen.generate_category(*category)?;
+    match gen.category_gap {
+        Some(gap_secs) if gen.concat => gen.generate_all_categories_sequence(gap_secs)?,
+        _ => {
+            for category in Category::all() {
+                gen.generate_category(*category)?;
+            }
+        }
     }
 
     // Generate special sets
     let original_duration = gen.duration;
     gen.duration = gen.duration.min(300.0);
-    gen.generate_binaural_set(200.0)?;
+    gen.generate_binaural_set(200.0, BandEdge::Mid)?;
     gen.generate_schumann()?;
     gen.duration = original_duration;
 
-    gen.generate_tuning_comparison()?;
+    gen.generate_tuning_comparison(false)?;
     gen.generate_chakra_meditation()?;
     gen.generate_om()?;
     gen.generate_noise_set()?;
 
     Ok(())
 }
+
+/// Generate one custom file per input line (normally stdin) of the form `<hz> [mode]`
+///
+/// Blank lines and lines starting with `#` are skipped.
+fn generate_from_lines(
+    gen: &AudioGenerator,
+    input: impl BufRead,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for (idx, line) in input.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let frequency: f64 = fields
+            .next()
+            .and_then(|f| f.parse().ok())
+            .ok_or_else(|| format!("line {}: invalid frequency in '{}'", idx + 1, line))?;
+        let mode = match fields.next() {
+            Some(mode) => GenerationMode::from_str(mode, true)
+                .map_err(|_| format!("line {}: unknown mode '{}'", idx + 1, mode))?,
+            None => GenerationMode::Sine,
+        };
+
+        let carrier = match mode {
+            GenerationMode::Sine => DEFAULT_CARRIER_HZ,
+            _ => gen.pick_carrier(frequency, None, false),
+        };
+        gen.generate_custom(frequency, &mode, carrier)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn batch_lines_generate_one_file_each() {
+        let gen = test_support::generator("batch_stdin", 0.5);
+        let input = "# comment\n528\n\n639 binaural\n";
+        generate_from_lines(&gen, input.as_bytes()).unwrap();
+
+        let names: Vec<String> = test_support::wav_files(&gen.output_dir)
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            ["custom_528.00hz_sine.wav", "custom_639.00hz_binaural.wav"]
+        );
+    }
+
+    #[test]
+    fn batch_lines_reject_unknown_modes() {
+        let gen = test_support::generator("batch_stdin_bad", 0.5);
+        assert!(generate_from_lines(&gen, "528 wobble\n".as_bytes()).is_err());
+    }
+}
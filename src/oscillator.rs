@@ -0,0 +1,166 @@
+//! Phase-accumulating oscillators that can be rendered in chunks.
+//!
+//! Fixed-frequency tones use the closed form `sin(2πft)`, which can be
+//! evaluated at any sample index. Anything whose frequency moves over time
+//! (glides, vibrato) has to integrate its phase instead, and that phase must
+//! be carried from one chunk to the next or every boundary clicks.
+
+use std::f64::consts::PI;
+
+use crate::config::AMPLITUDE;
+
+/// Oscillator phase carried across chunk boundaries
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhaseState {
+    /// Current phase in cycles, kept in `0.0..1.0`
+    pub phase: f64,
+    /// Number of samples rendered so far
+    pub position: u64,
+}
+
+impl PhaseState {
+    /// Sine of the current phase, then advance one sample at `frequency`
+    pub fn next_sine(&mut self, frequency: f64, sample_rate: u32) -> f64 {
+        let value = (2.0 * PI * self.phase).sin();
+        self.phase = (self.phase + frequency / sample_rate as f64).rem_euclid(1.0);
+        self.position += 1;
+        value
+    }
+}
+
+/// Basic oscillator shapes
+///
+/// The non-sine shapes are naive (not band-limited), so very high
+/// fundamentals will alias.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Waveform {
+    Sine,
+    Square,
+    /// Rising sawtooth
+    Saw,
+    Triangle,
+}
+
+impl Waveform {
+    /// Value in `-1.0..=1.0` at `phase` cycles (`0.0..1.0`)
+    pub fn sample(self, phase: f64) -> f64 {
+        match self {
+            Waveform::Sine => (2.0 * PI * phase).sin(),
+            Waveform::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Saw => 2.0 * phase - 1.0,
+            Waveform::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+        }
+    }
+}
+
+/// Linear pitch glide between two frequencies
+#[derive(Debug, Clone, Copy)]
+pub struct Glide {
+    pub start_freq: f64,
+    pub end_freq: f64,
+    pub total_samples: u64,
+    pub sample_rate: u32,
+}
+
+impl Glide {
+    /// Render the next `out.len()` samples, continuing from `state`
+    pub fn render(&self, state: &mut PhaseState, out: &mut [f64]) {
+        let total = self.total_samples.max(1) as f64;
+        for sample in out.iter_mut() {
+            let progress = (state.position as f64 / total).min(1.0);
+            let frequency = self.start_freq + (self.end_freq - self.start_freq) * progress;
+            *sample = AMPLITUDE * state.next_sine(frequency, self.sample_rate);
+        }
+    }
+}
+
+/// Endless fixed-frequency sine, produced one sample at a time
+///
+/// The real-time counterpart to `generate_sine_wave`: nothing is rendered
+/// ahead, so a consumer can pull samples for as long as it likes. The
+/// iterator never returns `None`.
+#[derive(Debug, Clone, Copy)]
+pub struct SineStream {
+    frequency: f64,
+    sample_rate: u32,
+    state: PhaseState,
+}
+
+impl SineStream {
+    pub fn new(frequency: f64, sample_rate: u32) -> Self {
+        Self {
+            frequency,
+            sample_rate,
+            state: PhaseState::default(),
+        }
+    }
+}
+
+impl Iterator for SineStream {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        Some(AMPLITUDE * self.state.next_sine(self.frequency, self.sample_rate))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn streamed_glide_matches_eager_glide() {
+        let glide = Glide {
+            start_freq: 200.0,
+            end_freq: 800.0,
+            total_samples: 44100,
+            sample_rate: 44100,
+        };
+
+        let mut eager = vec![0.0; 44100];
+        glide.render(&mut PhaseState::default(), &mut eager);
+
+        let mut state = PhaseState::default();
+        let mut streamed = vec![0.0; 44100];
+        for chunk in streamed
+            .chunks_mut(1000)
+            .flat_map(|chunk| chunk.chunks_mut(333))
+        {
+            glide.render(&mut state, chunk);
+        }
+
+        assert_eq!(streamed, eager);
+        assert_eq!(state.position, 44100);
+    }
+
+    #[test]
+    fn sine_stream_matches_the_eager_sine_and_never_ends() {
+        let gen = test_support::generator("sine-stream", 1.0);
+        let eager = gen.generate_sine_wave(441.7, 1.0);
+        let streamed: Vec<f64> = SineStream::new(441.7, 44100).take(eager.len()).collect();
+        assert_eq!(streamed.len(), eager.len());
+        for (s, e) in streamed.iter().zip(&eager) {
+            assert!((s - e).abs() < 1e-9);
+        }
+
+        let mut stream = SineStream::new(441.7, 44100);
+        assert_eq!(stream.size_hint(), (usize::MAX, None));
+        // Several minutes in, it still yields full-scale samples
+        let late: Vec<f64> = stream.by_ref().skip(20_000_000).take(1000).collect();
+        assert_eq!(late.len(), 1000);
+        let peak = late.iter().fold(0.0f64, |acc, s| acc.max(s.abs()));
+        assert!((peak - AMPLITUDE).abs() < 1e-3);
+        assert!(stream.next().is_some());
+    }
+}
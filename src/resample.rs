@@ -0,0 +1,105 @@
+//! Sample rate conversion for a single channel.
+
+use std::f64::consts::PI;
+
+/// Interpolation used when changing sample rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Resampler {
+    /// Straight-line interpolation between neighboring samples (fast, some aliasing)
+    Linear,
+    /// Blackman-windowed sinc interpolation, band-limited to the lower rate
+    #[default]
+    Sinc,
+}
+
+/// Zero crossings of the sinc kernel on each side of the interpolation point
+const SINC_HALF_WIDTH: usize = 32;
+
+/// Resample one channel from `from_rate` to `to_rate`
+pub fn resample(samples: &[f64], from_rate: u32, to_rate: u32, method: Resampler) -> Vec<f64> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let step = from_rate as f64 / to_rate as f64;
+    let len = (samples.len() as f64 / step).round() as usize;
+    match method {
+        Resampler::Linear => (0..len)
+            .map(|n| linear_at(samples, n as f64 * step))
+            .collect(),
+        Resampler::Sinc => {
+            // Downsampling narrows the passband to the new Nyquist
+            let cutoff = (to_rate as f64 / from_rate as f64).min(1.0) * 0.95;
+            (0..len)
+                .map(|n| sinc_at(samples, n as f64 * step, cutoff))
+                .collect()
+        }
+    }
+}
+
+/// Linearly interpolated value at fractional input position `position`
+fn linear_at(samples: &[f64], position: f64) -> f64 {
+    let index = position.floor() as usize;
+    let frac = position - index as f64;
+    let current = samples.get(index).copied().unwrap_or(0.0);
+    let next = samples.get(index + 1).copied().unwrap_or(current);
+    current + (next - current) * frac
+}
+
+/// Windowed-sinc interpolated value at `position`, with `cutoff` relative to input Nyquist
+fn sinc_at(samples: &[f64], position: f64, cutoff: f64) -> f64 {
+    let center = position.floor() as isize;
+    let half = SINC_HALF_WIDTH as f64 / cutoff;
+    let reach = half.ceil() as isize;
+
+    let first = (center - reach + 1).max(0);
+    let last = (center + reach).min(samples.len() as isize - 1);
+    (first..=last)
+        .map(|k| {
+            let x = position - k as f64;
+            samples[k as usize] * cutoff * sinc(cutoff * x) * blackman(x / half)
+        })
+        .sum()
+}
+
+/// Normalized sinc, sin(πx) / (πx)
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Blackman window over `-1.0..=1.0`, zero outside it
+fn blackman(x: f64) -> f64 {
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    let phase = PI * (x + 1.0);
+    0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converting_to_48k_keeps_the_tone_frequency() {
+        let tone: Vec<f64> = (0..44100)
+            .map(|i| 0.5 * (2.0 * PI * 1000.0 * i as f64 / 44100.0).sin())
+            .collect();
+
+        for method in [Resampler::Linear, Resampler::Sinc] {
+            let converted = resample(&tone, 44100, 48000, method);
+            assert_eq!(converted.len(), 48000);
+            let peak = crate::analysis::dominant_frequency(&converted, 48000, 100.0, 5000.0);
+            assert!(
+                (peak - 1000.0).abs() < 0.5,
+                "{:?} peak at {} Hz",
+                method,
+                peak
+            );
+        }
+    }
+}
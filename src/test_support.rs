@@ -0,0 +1,71 @@
+//! Helpers shared by the unit tests.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::analysis;
+use crate::config::AudioConfig;
+use crate::generator::AudioGenerator;
+use crate::wav;
+
+/// A fresh, empty directory under the system temp dir, unique to this call
+pub fn temp_dir(name: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "spirit-test-{}-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst),
+        name
+    ));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A generator writing `duration` seconds at 44.1 kHz into a fresh temp dir
+pub fn generator(name: &str, duration: f64) -> AudioGenerator {
+    AudioGenerator::new(temp_dir(name), duration, AudioConfig::default())
+}
+
+/// Read a WAV file back as `(channels, sample_rate, interleaved samples)`
+pub fn read(path: &Path) -> (u16, u32, Vec<f64>) {
+    wav::read_wav(path).unwrap()
+}
+
+/// WAV files directly inside `dir`, sorted by name
+pub fn wav_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wav"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Every `channels`-th sample starting at `index`, i.e. one channel of interleaved audio
+pub fn channel(samples: &[f64], channels: u16, index: usize) -> Vec<f64> {
+    samples
+        .iter()
+        .skip(index)
+        .step_by(channels as usize)
+        .copied()
+        .collect()
+}
+
+/// Number of times the `window`-sample RMS rises above `threshold` after
+/// dropping below a quarter of it, i.e. distinct pulses or beeps
+pub fn onsets(samples: &[f64], window: usize, threshold: f64) -> usize {
+    let mut quiet = true;
+    let mut count = 0;
+    for level in samples.chunks(window).map(analysis::rms) {
+        if quiet && level > threshold {
+            quiet = false;
+            count += 1;
+        } else if level < threshold / 4.0 {
+            quiet = true;
+        }
+    }
+    count
+}
@@ -0,0 +1,573 @@
+//! WAV sample encoding and 64-bit float file I/O.
+//!
+//! Integer and 32-bit float output goes through hound. hound cannot write
+//! 64-bit float data, so those files use the small RIFF writer and reader here.
+
+use std::fs;
+use std::io::{Seek, Write};
+use std::path::Path;
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+
+use crate::error::SpiritError;
+use crate::rng::{Lcg, NoiseRng};
+
+/// WAVE_FORMAT_PCM format tag
+const FORMAT_PCM: u16 = 1;
+/// WAVE_FORMAT_IEEE_FLOAT format tag
+const FORMAT_IEEE_FLOAT: u16 = 3;
+/// WAVE_FORMAT_EXTENSIBLE format tag
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/// Tail shared by the KSDATAFORMAT_SUBTYPE_* GUIDs after the format tag
+const SUBFORMAT_GUID_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+/// Speaker layout for multichannel output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ChannelLayout {
+    Mono,
+    Stereo,
+    /// Front left/right and back left/right
+    Quad,
+    /// Front left/right/center, LFE, and back left/right
+    #[value(name = "5.1")]
+    Surround51,
+}
+
+impl ChannelLayout {
+    pub fn channels(self) -> u16 {
+        match self {
+            ChannelLayout::Mono => 1,
+            ChannelLayout::Stereo => 2,
+            ChannelLayout::Quad => 4,
+            ChannelLayout::Surround51 => 6,
+        }
+    }
+
+    /// WAVE_FORMAT_EXTENSIBLE speaker position bits
+    pub fn mask(self) -> u32 {
+        const FRONT_LEFT: u32 = 0x1;
+        const FRONT_RIGHT: u32 = 0x2;
+        const FRONT_CENTER: u32 = 0x4;
+        const LOW_FREQUENCY: u32 = 0x8;
+        const BACK_LEFT: u32 = 0x10;
+        const BACK_RIGHT: u32 = 0x20;
+
+        match self {
+            ChannelLayout::Mono => FRONT_CENTER,
+            ChannelLayout::Stereo => FRONT_LEFT | FRONT_RIGHT,
+            ChannelLayout::Quad => FRONT_LEFT | FRONT_RIGHT | BACK_LEFT | BACK_RIGHT,
+            ChannelLayout::Surround51 => {
+                FRONT_LEFT | FRONT_RIGHT | FRONT_CENTER | LOW_FREQUENCY | BACK_LEFT | BACK_RIGHT
+            }
+        }
+    }
+
+    /// Layout with the given channel count, if there is a standard one
+    pub fn for_channels(channels: u16) -> Option<Self> {
+        [
+            ChannelLayout::Mono,
+            ChannelLayout::Stereo,
+            ChannelLayout::Quad,
+            ChannelLayout::Surround51,
+        ]
+        .into_iter()
+        .find(|layout| layout.channels() == channels)
+    }
+}
+
+/// Dither applied when reducing to 16-bit integer samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dither {
+    /// Triangular (TPDF) dither with a flat noise spectrum
+    Tpdf,
+    /// TPDF dither with second-order error feedback, moving the noise toward Nyquist
+    NoiseShaped,
+}
+
+/// Quantize interleaved samples to 16 bits with dither
+///
+/// Each channel keeps its own error history. Noise shaping feeds the
+/// quantization error back through `(1 - z^-1)^2`, which lowers the noise
+/// floor at low frequencies in exchange for more noise near Nyquist.
+pub fn dither_i16(samples: &[f64], channels: u16, dither: Dither, seed: u64) -> Vec<i16> {
+    let scale = i16::MAX as f64;
+    let channels = channels.max(1) as usize;
+    let mut rng = Lcg::new(seed);
+    let mut errors = vec![[0.0f64; 2]; channels];
+
+    samples
+        .iter()
+        .enumerate()
+        .map(|(i, &sample)| {
+            let history = &mut errors[i % channels];
+            let target = sample.clamp(-1.0, 1.0) * scale;
+            let shaped = match dither {
+                Dither::Tpdf => target,
+                Dither::NoiseShaped => target - (2.0 * history[0] - history[1]),
+            };
+            let noise = rng.next_unit() - rng.next_unit();
+            let quantized = (shaped + noise).round().clamp(-scale - 1.0, scale);
+            *history = [quantized - shaped, history[0]];
+            quantized as i16
+        })
+        .collect()
+}
+
+/// Convert f64 sample to i16
+fn convert_sample_i16(sample: f64) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f64) as i16
+}
+
+/// Convert f64 sample to i32 (24-bit)
+fn convert_sample_i32_24bit(sample: f64) -> i32 {
+    (sample.clamp(-1.0, 1.0) * 8388607.0) as i32
+}
+
+/// Convert f64 sample to i32
+fn convert_sample_i32(sample: f64) -> i32 {
+    (sample.clamp(-1.0, 1.0) * i32::MAX as f64) as i32
+}
+
+/// Replace NaN and infinite samples with silence
+///
+/// Returns the cleaned samples and the number replaced, or `None` if every
+/// sample was already finite.
+pub fn sanitize(samples: &[f64]) -> Option<(Vec<f64>, usize)> {
+    let count = samples.iter().filter(|s| !s.is_finite()).count();
+    if count == 0 {
+        return None;
+    }
+
+    let clean = samples
+        .iter()
+        .map(|&s| if s.is_finite() { s } else { 0.0 })
+        .collect();
+    Some((clean, count))
+}
+
+/// Write interleaved samples to a WAV writer based on its spec
+pub fn write_samples<W: Write + Seek>(
+    writer: &mut WavWriter<W>,
+    samples: &[f64],
+    spec: WavSpec,
+) -> Result<(), SpiritError> {
+    match (spec.sample_format, spec.bits_per_sample) {
+        (SampleFormat::Float, _) => {
+            for &sample in samples {
+                writer.write_sample(sample as f32)?;
+            }
+        }
+        (SampleFormat::Int, 16) => {
+            for &sample in samples {
+                writer.write_sample(convert_sample_i16(sample))?;
+            }
+        }
+        (SampleFormat::Int, 24) => {
+            for &sample in samples {
+                writer.write_sample(convert_sample_i32_24bit(sample))?;
+            }
+        }
+        (SampleFormat::Int, _) => {
+            for &sample in samples {
+                writer.write_sample(convert_sample_i32(sample))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read any supported WAV file as `(channels, sample_rate, interleaved samples)`
+///
+/// Integer samples are scaled to the -1.0..1.0 range.
+pub fn read_wav(path: &Path) -> Result<(u16, u32, Vec<f64>), SpiritError> {
+    // hound rejects 64-bit float files, which our own reader handles
+    let mut reader = match WavReader::open(path) {
+        Ok(reader) => reader,
+        Err(err) => return read_f64_wav(path).map_err(|_| err.into()),
+    };
+
+    let spec = reader.spec();
+    if spec.sample_format == SampleFormat::Float && spec.bits_per_sample == 64 {
+        return read_f64_wav(path);
+    }
+
+    let samples = match spec.sample_format {
+        SampleFormat::Float => reader
+            .samples::<f32>()
+            .map(|s| s.map(f64::from))
+            .collect::<Result<Vec<_>, _>>()?,
+        SampleFormat::Int => {
+            let full_scale = (1i64 << (spec.bits_per_sample - 1)) as f64;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|v| v as f64 / full_scale))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+
+    Ok((spec.channels, spec.sample_rate, samples))
+}
+
+/// RIFF and data chunk sizes for `sample_count` samples after `header_len` bytes
+///
+/// Sizes are computed in 64 bits; anything past the 4 GiB a RIFF size field
+/// can hold is rejected rather than silently wrapping.
+fn chunk_sizes(
+    sample_count: usize,
+    bytes_per_sample: u32,
+    header_len: u32,
+) -> Result<(u32, u32), SpiritError> {
+    let data_len = sample_count as u64 * bytes_per_sample as u64;
+    let riff_len = header_len as u64 + data_len;
+    match (u32::try_from(riff_len), u32::try_from(data_len)) {
+        (Ok(riff_len), Ok(data_len)) => Ok((riff_len, data_len)),
+        _ => Err(SpiritError::UnsupportedFormat(format!(
+            "{} bytes of audio exceeds the 4 GiB WAV size limit",
+            data_len
+        ))),
+    }
+}
+
+/// Write interleaved samples as a 64-bit IEEE float WAV file
+pub fn write_f64_wav(
+    path: &Path,
+    channels: u16,
+    sample_rate: u32,
+    samples: &[f64],
+) -> Result<(), SpiritError> {
+    let block_align = channels as u32 * 8;
+    let (riff_len, data_len) = chunk_sizes(samples.len(), 8, 50)?;
+    let frames = data_len / block_align;
+
+    let mut out = Vec::with_capacity(58 + data_len as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&riff_len.to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&18u32.to_le_bytes());
+    out.extend_from_slice(&FORMAT_IEEE_FLOAT.to_le_bytes());
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * block_align).to_le_bytes());
+    out.extend_from_slice(&(block_align as u16).to_le_bytes());
+    out.extend_from_slice(&64u16.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+
+    // Non-PCM formats carry a fact chunk with the frame count
+    out.extend_from_slice(b"fact");
+    out.extend_from_slice(&4u32.to_le_bytes());
+    out.extend_from_slice(&frames.to_le_bytes());
+
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for &sample in samples {
+        out.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    fs::write(path, out)?;
+    Ok(())
+}
+
+/// Append cue points with text labels to an existing WAV file
+///
+/// Each cue is `(frame offset, label)`. The points go in a `cue ` chunk and
+/// the labels in a `LIST`/`adtl` chunk, after which the RIFF size is patched.
+pub fn append_cue_points(path: &Path, cues: &[(u32, String)]) -> Result<(), SpiritError> {
+    let mut bytes = fs::read(path)?;
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(SpiritError::UnsupportedFormat(
+            "not a RIFF WAVE file".to_string(),
+        ));
+    }
+    // Keep the appended chunks word-aligned
+    if bytes.len() % 2 == 1 {
+        bytes.push(0);
+    }
+
+    bytes.extend_from_slice(b"cue ");
+    bytes.extend_from_slice(&(4 + 24 * cues.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(cues.len() as u32).to_le_bytes());
+    for (idx, (offset, _)) in cues.iter().enumerate() {
+        let id = idx as u32 + 1;
+        bytes.extend_from_slice(&id.to_le_bytes());
+        bytes.extend_from_slice(&offset.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&offset.to_le_bytes());
+    }
+
+    let mut adtl = b"adtl".to_vec();
+    for (idx, (_, label)) in cues.iter().enumerate() {
+        let text_len = label.len() as u32 + 1;
+        adtl.extend_from_slice(b"labl");
+        adtl.extend_from_slice(&(4 + text_len).to_le_bytes());
+        adtl.extend_from_slice(&(idx as u32 + 1).to_le_bytes());
+        adtl.extend_from_slice(label.as_bytes());
+        adtl.push(0);
+        if text_len % 2 == 1 {
+            adtl.push(0);
+        }
+    }
+    bytes.extend_from_slice(b"LIST");
+    bytes.extend_from_slice(&(adtl.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&adtl);
+
+    let riff_len = bytes.len() as u32 - 8;
+    bytes[4..8].copy_from_slice(&riff_len.to_le_bytes());
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Write a `.cue` sheet describing tracks within a WAV file
+///
+/// Each track is `(frame offset, title)`; offsets are converted to the
+/// `MM:SS:FF` index format, which counts 75 frames per second.
+pub fn write_cue_sheet(
+    cue_path: &Path,
+    wav_path: &Path,
+    title: &str,
+    tracks: &[(u32, String)],
+    sample_rate: u32,
+) -> Result<(), SpiritError> {
+    let file_name = wav_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut sheet = format!("TITLE \"{}\"\nFILE \"{}\" WAVE\n", title, file_name);
+    for (idx, (offset, track_title)) in tracks.iter().enumerate() {
+        let cue_frames = *offset as u64 * 75 / sample_rate as u64;
+        sheet.push_str(&format!(
+            "  TRACK {:02} AUDIO\n    TITLE \"{}\"\n    INDEX 01 {:02}:{:02}:{:02}\n",
+            idx + 1,
+            track_title,
+            cue_frames / (75 * 60),
+            cue_frames / 75 % 60,
+            cue_frames % 75
+        ));
+    }
+
+    fs::write(cue_path, sheet)?;
+    Ok(())
+}
+
+/// Write interleaved samples as a WAVE_FORMAT_EXTENSIBLE file with a channel mask
+///
+/// hound always derives the mask from the channel count, which maps quad
+/// output to front/center/LFE instead of the four corners. This writer takes
+/// the mask explicitly and supports the same sample formats as the rest of
+/// the crate (16/24/32-bit integer, 32/64-bit float).
+pub fn write_extensible_wav(
+    path: &Path,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+    float: bool,
+    channel_mask: u32,
+    samples: &[f64],
+) -> Result<(), SpiritError> {
+    let bytes_per_sample = bits_per_sample as u32 / 8;
+    let block_align = channels as u32 * bytes_per_sample;
+    // Non-PCM formats carry a fact chunk with the frame count
+    let fact_len = if float { 12 } else { 0 };
+    let (riff_len, data_len) = chunk_sizes(samples.len(), bytes_per_sample, 60 + fact_len)?;
+    let frames = data_len / block_align;
+
+    let mut out = Vec::with_capacity(68 + fact_len as usize + data_len as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&riff_len.to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&40u32.to_le_bytes());
+    out.extend_from_slice(&FORMAT_EXTENSIBLE.to_le_bytes());
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * block_align).to_le_bytes());
+    out.extend_from_slice(&(block_align as u16).to_le_bytes());
+    out.extend_from_slice(&bits_per_sample.to_le_bytes());
+    out.extend_from_slice(&22u16.to_le_bytes());
+    out.extend_from_slice(&bits_per_sample.to_le_bytes());
+    out.extend_from_slice(&channel_mask.to_le_bytes());
+    let subformat = if float { FORMAT_IEEE_FLOAT } else { FORMAT_PCM };
+    out.extend_from_slice(&subformat.to_le_bytes());
+    out.extend_from_slice(&SUBFORMAT_GUID_TAIL);
+
+    if float {
+        out.extend_from_slice(b"fact");
+        out.extend_from_slice(&4u32.to_le_bytes());
+        out.extend_from_slice(&frames.to_le_bytes());
+    }
+
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for &sample in samples {
+        match (float, bits_per_sample) {
+            (true, 64) => out.extend_from_slice(&sample.to_le_bytes()),
+            (true, _) => out.extend_from_slice(&(sample as f32).to_le_bytes()),
+            (false, 16) => out.extend_from_slice(&convert_sample_i16(sample).to_le_bytes()),
+            (false, 24) => {
+                out.extend_from_slice(&convert_sample_i32_24bit(sample).to_le_bytes()[..3])
+            }
+            (false, _) => out.extend_from_slice(&convert_sample_i32(sample).to_le_bytes()),
+        }
+    }
+
+    fs::write(path, out)?;
+    Ok(())
+}
+
+/// Read a 64-bit IEEE float WAV file as `(channels, sample_rate, samples)`
+pub fn read_f64_wav(path: &Path) -> Result<(u16, u32, Vec<f64>), SpiritError> {
+    let bytes = fs::read(path)?;
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(SpiritError::UnsupportedFormat(
+            "not a RIFF WAVE file".to_string(),
+        ));
+    }
+
+    let mut format = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().unwrap()) as usize;
+        let body = &bytes[pos + 8..(pos + 8 + size).min(bytes.len())];
+
+        match id {
+            b"fmt " if body.len() >= 16 => {
+                let mut tag = u16::from_le_bytes([body[0], body[1]]);
+                if tag == FORMAT_EXTENSIBLE && body.len() >= 26 {
+                    tag = u16::from_le_bytes([body[24], body[25]]);
+                }
+                let bits = u16::from_le_bytes([body[14], body[15]]);
+                if tag != FORMAT_IEEE_FLOAT || bits != 64 {
+                    return Err(SpiritError::UnsupportedFormat(
+                        "not a 64-bit float WAV file".to_string(),
+                    ));
+                }
+                let channels = u16::from_le_bytes([body[2], body[3]]);
+                let sample_rate = u32::from_le_bytes(body[4..8].try_into().unwrap());
+                format = Some((channels, sample_rate));
+            }
+            b"data" => {
+                let (channels, sample_rate) = format.ok_or_else(|| {
+                    SpiritError::UnsupportedFormat("data chunk before fmt chunk".to_string())
+                })?;
+                let samples = body
+                    .chunks_exact(8)
+                    .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+                    .collect();
+                return Ok((channels, sample_rate, samples));
+            }
+            _ => {}
+        }
+
+        // Chunks are padded to an even number of bytes
+        pos += 8 + size + (size & 1);
+    }
+
+    Err(SpiritError::UnsupportedFormat(
+        "missing data chunk".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[test]
+    fn f64_wav_round_trips_exactly() {
+        let dir = test_support::temp_dir("f64-round-trip");
+        let path = dir.join("tone.wav");
+        let samples: Vec<f64> = (0..1000)
+            .map(|i| (i as f64 * 0.0123).sin() * std::f64::consts::FRAC_1_SQRT_2)
+            .collect();
+
+        write_f64_wav(&path, 2, 48000, &samples).unwrap();
+        let (channels, rate, read_back) = read_f64_wav(&path).unwrap();
+        assert_eq!((channels, rate), (2, 48000));
+        assert_eq!(read_back, samples);
+
+        let (_, _, via_read_wav) = read_wav(&path).unwrap();
+        assert_eq!(via_read_wav, samples);
+    }
+
+    #[test]
+    fn chunk_sizes_reject_data_past_the_riff_limit() {
+        assert_eq!(chunk_sizes(1000, 8, 50).unwrap(), (8050, 8000));
+
+        // 2^29 f64 samples are exactly 4 GiB, one byte more than a u32 holds
+        let too_many = 1usize << 29;
+        assert!(matches!(
+            chunk_sizes(too_many, 8, 50),
+            Err(SpiritError::UnsupportedFormat(_))
+        ));
+        // The header alone can push a data chunk that fits over the limit
+        let fits = (u32::MAX / 4) as usize;
+        assert!(chunk_sizes(fits, 4, 0).is_ok());
+        assert!(chunk_sizes(fits, 4, 72).is_err());
+    }
+
+    #[test]
+    fn sanitize_replaces_non_finite_samples() {
+        assert_eq!(sanitize(&[0.5, -0.5]), None);
+        assert_eq!(
+            sanitize(&[0.5, f64::NAN, f64::INFINITY, -0.5, f64::NEG_INFINITY]),
+            Some((vec![0.5, 0.0, 0.0, -0.5, 0.0], 3))
+        );
+    }
+
+    #[test]
+    fn quad_header_carries_the_corner_speaker_mask() {
+        let path = test_support::temp_dir("quad-mask").join("quad.wav");
+        let layout = ChannelLayout::Quad;
+        let samples = vec![0.25; 400];
+        write_extensible_wav(
+            &path,
+            layout.channels(),
+            44100,
+            16,
+            false,
+            layout.mask(),
+            &samples,
+        )
+        .unwrap();
+
+        let bytes = fs::read(&path).unwrap();
+        assert_eq!(&bytes[12..16], b"fmt ");
+        let mask = u32::from_le_bytes(bytes[40..44].try_into().unwrap());
+        assert_eq!(mask, 0x1 | 0x2 | 0x10 | 0x20);
+
+        let (channels, rate, read_back) = read_wav(&path).unwrap();
+        assert_eq!((channels, rate, read_back.len()), (4, 44100, 400));
+    }
+
+    #[test]
+    fn noise_shaping_lowers_the_low_frequency_noise_floor() {
+        let rate = 44100.0;
+        let tone: Vec<f64> = (0..1 << 16)
+            .map(|i| 0.001 * (2.0 * std::f64::consts::PI * 1000.0 * i as f64 / rate).sin())
+            .collect();
+
+        // Energy of the quantization error below 2 kHz
+        let low_noise = |dither: Dither| {
+            let error: Vec<f64> = dither_i16(&tone, 1, dither, 7)
+                .iter()
+                .zip(&tone)
+                .map(|(&q, &s)| q as f64 - s * i16::MAX as f64)
+                .collect();
+            let spectrum = crate::analysis::magnitude_spectrum(&error);
+            let limit = (2000.0 / rate * error.len() as f64) as usize;
+            spectrum[1..limit].iter().map(|m| m * m).sum::<f64>()
+        };
+
+        let flat = low_noise(Dither::Tpdf);
+        let shaped = low_noise(Dither::NoiseShaped);
+        assert!(shaped < flat / 10.0, "{} vs {}", shaped, flat);
+    }
+}
@@ -0,0 +1,5 @@
+//! Runs the unit tests in `build.rs`, which cargo never builds as a test itself
+
+#[allow(dead_code)]
+#[path = "../build.rs"]
+mod build;